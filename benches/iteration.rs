@@ -0,0 +1,78 @@
+use std::pin::pin;
+
+use criterion::{
+    black_box,
+    criterion_group,
+    criterion_main,
+    BatchSize,
+    Criterion,
+    Throughput,
+};
+use remit::{
+    Generator,
+    Remit,
+};
+
+const ITEMS: u64 = 1_000;
+
+async fn lazy(remit: Remit<'_, u64>) {
+    for i in 0..ITEMS {
+        remit.value(i).await;
+    }
+}
+
+async fn eager(remit: Remit<'_, u64>) {
+    for i in 0..ITEMS {
+        drop(remit.value(i));
+    }
+}
+
+async fn pairs(remit: Remit<'_, u64>) {
+    for i in 0..ITEMS / 2 {
+        drop(remit.value(i));
+        remit.value(i).await;
+    }
+}
+
+fn per_item(c: &mut Criterion) {
+    let mut group = c.benchmark_group("per_item");
+    group.throughput(Throughput::Elements(ITEMS));
+    group.bench_function("pinned/lazy", |b| b.iter(|| {
+        pin!(Generator::new()).of(lazy).map(black_box).sum::<u64>()
+    }));
+    group.bench_function("boxed/lazy", |b| b.iter(|| {
+        Generator::boxed(lazy).map(black_box).sum::<u64>()
+    }));
+    group.bench_function("pinned/pairs", |b| b.iter(|| {
+        pin!(Generator::new()).of(pairs).map(black_box).sum::<u64>()
+    }));
+    group.bench_function("boxed/pairs", |b| b.iter(|| {
+        Generator::boxed(pairs).map(black_box).sum::<u64>()
+    }));
+    group.bench_function("pinned/eager", |b| b.iter(|| {
+        pin!(Generator::new()).of(eager).map(black_box).sum::<u64>()
+    }));
+    group.bench_function("boxed/eager", |b| b.iter(|| {
+        Generator::boxed(eager).map(black_box).sum::<u64>()
+    }));
+    group.finish();
+}
+
+fn creation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("creation");
+    group.bench_function("pinned", |b| b.iter(|| {
+        pin!(Generator::new()).of(lazy).next()
+    }));
+    group.bench_function("boxed", |b| b.iter(|| {
+        Generator::boxed(lazy).next()
+    }));
+    group.bench_function("boxed/drop", |b| b.iter_batched(
+        || Generator::boxed(lazy),
+        drop,
+        BatchSize::SmallInput,
+    ));
+    group.finish();
+}
+
+criterion_group!(benches, per_item, creation);
+criterion_main!(benches);
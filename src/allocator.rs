@@ -0,0 +1,267 @@
+use core::{
+    alloc::Layout,
+    cell::{
+        Cell,
+        UnsafeCell,
+    },
+    marker::PhantomPinned,
+    mem::{
+        self,
+        ManuallyDrop,
+    },
+    ops::AsyncFnOnce,
+    ptr::{
+        addr_of,
+        addr_of_mut,
+        NonNull,
+    },
+};
+
+use alloc::alloc::handle_alloc_error;
+
+use allocator_api2::{
+    alloc::{
+        AllocError,
+        Allocator,
+    },
+    boxed::Box,
+    vec::Vec,
+};
+
+use crate::{
+    Exchange,
+    Generator,
+    GeneratorIterator,
+    Mode,
+    Owner,
+    References,
+    Remit,
+    StaticCall,
+};
+
+/// Allocates with the erased allocator.
+type Allocate = unsafe fn(*const (), Layout) -> Result<NonNull<[u8]>, AllocError>;
+
+/// The allocator held by an [`AllocatedCycler`], with its type erased,
+/// such that the values in transit may be spilled into it.
+#[derive(Clone, Copy)]
+pub(crate) struct Erased {
+    alloc: *const (),
+    allocate: Allocate,
+    deallocate: unsafe fn(*const (), NonNull<u8>, Layout),
+}
+
+impl Erased {
+    /// The ptr must remain valid for as long as anything allocated remains.
+    unsafe fn new<A: Allocator>(alloc: *const A) -> Self {
+        // SOUND: unsafe-fn, with the ptr to the allocator's type
+        Erased {
+            alloc: alloc.cast(),
+            allocate: |alloc, layout| unsafe { (*alloc.cast::<A>()).allocate(layout) },
+            deallocate: |alloc, ptr, layout| unsafe { (*alloc.cast::<A>()).deallocate(ptr, layout) },
+        }
+    }
+}
+
+// SOUND: forwards to the held allocator, which is valid while anything it allocated remains,
+// as the spilled values are dropped before it.
+unsafe impl Allocator for Erased {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        // SOUND: see above
+        unsafe { (self.allocate)(self.alloc, layout) }
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        (self.deallocate)(self.alloc, ptr, layout)
+    }
+}
+
+/// The values in transit beyond the inline pair, allocated by the custom allocator.
+///
+/// Boxed, such that the exchange is no larger than with a `VecDeque`.
+pub(crate) struct Spill<T>(Box<Stacks<T>, Erased>);
+
+/// A queue of two stacks, with `front` reversed, such that both ends are amortized constant time.
+struct Stacks<T> {
+    front: Vec<T, Erased>,
+    back: Vec<T, Erased>,
+}
+
+impl<T> Spill<T> {
+    pub(crate) fn new(alloc: Erased, values: [T; 3]) -> Self {
+        let mut back = Vec::with_capacity_in(4, alloc);
+        back.extend(values);
+        Spill(Box::new_in(Stacks {
+            front: Vec::new_in(alloc),
+            back,
+        }, alloc))
+    }
+
+    pub(crate) fn push_back(&mut self, value: T) {
+        self.0.back.push(value)
+    }
+
+    pub(crate) fn pop_front(&mut self) -> Option<T> {
+        let stacks = &mut *self.0;
+        if stacks.front.is_empty() {
+            mem::swap(&mut stacks.front, &mut stacks.back);
+            stacks.front.reverse();
+        }
+        stacks.front.pop()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.front.len() + self.0.back.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.front.is_empty() && self.0.back.is_empty()
+    }
+}
+
+#[repr(C)]
+/// A single allocation from a custom allocator, holding the future along with the values in transit.
+///
+/// The same as a `Cycler`, but also holding the allocator until it is freed.
+// NEED: `references` is first, such that a ptr to it is a ptr to the AllocatedCycler
+struct AllocatedCycler<P, T, A: Allocator> {
+    references: References<T>,
+    future: UnsafeCell<Option<P>>,
+    alloc: ManuallyDrop<A>,
+    _pin: PhantomPinned,
+}
+
+impl<P, T, A: Allocator> AllocatedCycler<P, T, A> {
+    /// Creates the allocation, shared by the owner, and the single `Remit` created from the mode.
+    /// Also provides the slot for the future.
+    fn allocate(alloc: A) -> (Owner<T>, Mode<'static, T>, *mut Option<P>) {
+        let layout = Layout::new::<Self>();
+        let ptr = match alloc.allocate(layout) {
+            Ok(ptr) => ptr.cast::<Self>().as_ptr(),
+            Err(_) => handle_alloc_error(layout),
+        };
+        // SOUND: freshly allocated with the layout of Self
+        unsafe {
+            ptr.write(AllocatedCycler {
+                references: References {
+                    interchange: Exchange::new(),
+                    alive: Cell::new(true),
+                    count: Cell::new(2),
+                    close: AllocatedCycler::<P, T, A>::close,
+                    free: AllocatedCycler::<P, T, A>::free,
+                    alloc: None,
+                },
+                future: UnsafeCell::new(None),
+                alloc: ManuallyDrop::new(alloc),
+                _pin: PhantomPinned,
+            });
+            // The allocator is never moved until freed, which is after the values are dropped
+            (*ptr).references.alloc = Some(Erased::new::<A>(addr_of!((*ptr).alloc).cast()));
+        };
+        // SOUND: freshly allocated, and not yet shared
+        let references = unsafe { addr_of!((*ptr).references) };
+        // SOUND: see above
+        let future = unsafe { (*ptr).future.get() };
+        (Owner(references), Mode::Boxed(references), future)
+    }
+
+    /// ptr must be from this AllocatedCycler's allocate, and not freed.
+    // NEED: erasing AllocatedCycler's storage generic, which ends up recursive
+    unsafe fn close(ptr: *const References<T>) {
+        let ptr: *const AllocatedCycler<P, T, A> = ptr as _;
+        // SOUND: (valid-ptr) repr(C) with references first
+        // SOUND: (&mut exclusive) only the owner touches the future, which is closing
+        *(*ptr).future.get() = None;
+    }
+
+    /// ptr must be from this AllocatedCycler's allocate, and may only be called once.
+    // NEED: erasing AllocatedCycler's storage generic, which ends up recursive
+    unsafe fn free(ptr: *const References<T>) {
+        let ptr: *mut AllocatedCycler<P, T, A> = ptr as _;
+        // SOUND: (valid-ptr) repr(C) with references first, written in allocate
+        // SOUND: (double-drop) can only be called once, and the allocator is never dropped in place
+        //
+        // NEED: the spilled values are dropped while the allocator remains
+        addr_of_mut!((*ptr).future).drop_in_place();
+        addr_of_mut!((*ptr).references).drop_in_place();
+        let alloc = ManuallyDrop::take(&mut (*ptr).alloc);
+        // SOUND: allocated by this allocator, with the same layout
+        alloc.deallocate(NonNull::new_unchecked(ptr).cast(), Layout::new::<Self>());
+    }
+}
+
+impl<T, P> Generator<T, P> {
+    /// The same as [`Generator::boxed()`], but the allocation is made by the provided allocator,
+    /// such as a per-request arena.
+    ///
+    /// The allocator is held until the allocation is freed,
+    /// and also allocates the buffer of values remitted without being awaited, beyond the first two.
+    ///
+    /// As the allocator may be borrowed, the same lifetime relaxation as [`Generator::of()`] applies,
+    /// such that the generator must accept any lifetime.
+    ///
+    /// ```
+    /// # use allocator_api2::alloc::Global;
+    /// # use remit::{Generator, Remit};
+    /// async fn gen(remit: Remit<'_, usize>) {
+    ///     for i in 1..=3 {
+    ///         remit.value(i).await;
+    ///     }
+    /// }
+    /// assert_eq!(vec![1, 2, 3], Generator::boxed_in(Global, gen).collect::<Vec<_>>());
+    /// ```
+    ///
+    /// Values remitted without awaiting are buffered in the same allocator.
+    /// ```
+    /// # use std::{alloc::Layout, cell::Cell, ptr::NonNull};
+    /// # use allocator_api2::alloc::{AllocError, Allocator, Global};
+    /// # use remit::{Generator, Remit};
+    /// struct Counting<'a>(&'a Cell<usize>);
+    ///
+    /// unsafe impl Allocator for Counting<'_> {
+    ///     fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+    ///         self.0.set(self.0.get() + 1);
+    ///         Global.allocate(layout)
+    ///     }
+    ///
+    ///     unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+    ///         Global.deallocate(ptr, layout)
+    ///     }
+    /// }
+    ///
+    /// async fn eager(remit: Remit<'_, usize>) {
+    ///     for i in 1..=5 {
+    ///         let _ = remit.value(i);
+    ///     }
+    /// }
+    /// let allocations = Cell::new(0);
+    /// let mut iter = Generator::boxed_in(Counting(&allocations), eager);
+    /// assert_eq!(Some(1), iter.next());
+    /// // The generator, and the spilled values
+    /// assert!(allocations.get() > 1);
+    /// assert_eq!(vec![2, 3, 4, 5], iter.collect::<Vec<_>>());
+    /// ```
+    pub fn boxed_in<'a, A, G>(alloc: A, gen: G) -> GeneratorIterator<'a, T, P>
+        where
+            A: Allocator + 'a,
+            // insures fn is not implemented only for 'static
+            G: for<'b> AsyncFnOnce(Remit<'b, T>),
+            // insures P is properly defined, even if it actually has a lifetime
+            G: StaticCall<(Remit<'static, T>, ), Output=P>,
+    {
+        let (owner, mode, future) = AllocatedCycler::allocate(alloc);
+        // SOUND: Writing to an UnsafeCell.
+        // Only spot where it's being written, having been freshly created.
+        //
+        // NEED: unsafe-cell lets shared-references to not conflict with exclusive-reference to future
+        unsafe { *future = Some(gen.call_static((Remit(mode), ))) };
+
+        GeneratorIterator {
+            done: false,
+            mode,
+            future,
+            _owner: Some(owner),
+            _storage: None,
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use core::{
+    any::TypeId,
+    future::{
+        poll_fn,
+        Future,
+    },
+    mem::ManuallyDrop,
+    pin::pin,
+    ptr,
+    task::{
+        Context,
+        Poll,
+        RawWaker,
+        RawWakerVTable,
+        Waker,
+    },
+};
+
+use crate::Remit;
+
+/// The remit of an ambient generator, carried by the waker of the context it is polled with.
+struct Target {
+    type_id: TypeId,
+    /// A `Remit<'_, T>` for the `T` of the type_id.
+    remit: *const (),
+    /// The waker that was replaced, which may also be a Target.
+    parent: *const Waker,
+}
+
+/// Forwards to the parent waker, as the Target only lives while being polled.
+static AMBIENT_V_TABLE: RawWakerVTable = RawWakerVTable::new(
+    clone_parent,
+    wake_parent,
+    wake_parent,
+    |_| {},
+);
+
+/// The ptr must be of a Target, which lives while being polled.
+unsafe fn parent<'a>(ptr: *const ()) -> &'a Waker {
+    &*(*ptr.cast::<Target>()).parent
+}
+
+unsafe fn clone_parent(ptr: *const ()) -> RawWaker {
+    // NEED: a clone may outlive the Target, so it is instead of the parent
+    let waker = ManuallyDrop::new(parent(ptr).clone());
+    RawWaker::new(waker.data(), waker.vtable())
+}
+
+unsafe fn wake_parent(ptr: *const ()) {
+    parent(ptr).wake_by_ref();
+}
+
+impl<T: 'static> Remit<'_, T> {
+    /// Polls the future such that [`yield_value()`] remits to this generator,
+    /// without passing the `Remit` to the future.
+    ///
+    /// When made ambient more than once, such as for different types,
+    /// values are remitted to the innermost of the matching type.
+    ///
+    /// ```
+    /// # use std::pin::pin;
+    /// # use remit::{yield_value, Generator, Remit};
+    /// async fn digits(number: u32) {
+    ///     for digit in number.to_string().chars() {
+    ///         yield_value(digit).await;
+    ///     }
+    /// }
+    /// async fn gen(remit: Remit<'_, char>) {
+    ///     remit.ambient(async {
+    ///         digits(12).await;
+    ///         digits(345).await;
+    ///     }).await
+    /// }
+    /// assert_eq!(
+    ///     vec!['1', '2', '3', '4', '5'],
+    ///     pin!(Generator::new()).of(gen).collect::<Vec<_>>(),
+    /// );
+    /// ```
+    pub fn ambient<'r, F: Future + 'r>(&'r self, future: F) -> impl Future<Output=F::Output> + 'r {
+        async move {
+            let mut future = pin!(future);
+            poll_fn(|cx| {
+                let target = Target {
+                    type_id: TypeId::of::<T>(),
+                    remit: ptr::from_ref(self).cast(),
+                    parent: cx.waker(),
+                };
+                // SOUND: the Target outlives the waker, and only forwards clones to the parent
+                let waker = unsafe { Waker::from_raw(RawWaker::new(ptr::from_ref(&target).cast(), &AMBIENT_V_TABLE)) };
+                future.as_mut().poll(&mut Context::from_waker(&waker))
+            }).await
+        }
+    }
+}
+
+/// Finds the innermost ambient remit for `T`, while polled.
+fn find<'a, T: 'static>(cx: &'a Context<'_>) -> &'a Remit<'a, T> {
+    let mut waker = cx.waker();
+    while ptr::eq(waker.vtable(), &AMBIENT_V_TABLE) {
+        // SOUND: only our vtable is used with a Target, which lives while being polled
+        let target = unsafe { &*waker.data().cast::<Target>() };
+        if target.type_id == TypeId::of::<T>() {
+            // SOUND: (valid-ptr) the Remit is borrowed by the ambient future while being polled
+            // SOUND: (type) the TypeId matches the T of the Remit
+            return unsafe { &*target.remit.cast::<Remit<'a, T>>() }
+        }
+        // SOUND: the parent outlives the Target
+        waker = unsafe { &*target.parent };
+    }
+    panic!("yield_value requires an ambient generator of the same type, from Remit::ambient()");
+}
+
+/// Remits the value to the innermost generator of the same type that was made [ambient](Remit::ambient()).
+///
+/// Otherwise, the same as [`Remit::value()`].
+///
+/// # Panics
+///
+/// Panics when polled outside of an ambient generator of the same type.
+///
+/// ```should_panic
+/// # use std::pin::pin;
+/// # use remit::{yield_value, Generator, Remit};
+/// async fn gen(remit: Remit<'_, u8>) {
+///     remit.ambient(yield_value("not a u8")).await
+/// }
+/// pin!(Generator::new()).of(gen).for_each(drop);
+/// ```
+pub fn yield_value<T: 'static>(value: T) -> impl Future<Output=()> {
+    let mut value = Some(value);
+    poll_fn(move |cx| {
+        let remit = find::<T>(cx);
+        if let Some(value) = value.take() {
+            #[cfg(feature = "alloc")]
+            // SOUND: non-public field, valid at instantiation
+            // SOUND: the Remit is not dropped, while being polled
+            //
+            // NEED: use-after-free prevention of value-exchange
+            if !unsafe { remit.0.strong() } {
+                return Poll::Pending
+            }
+            remit.0.push(value);
+        }
+        #[cfg(feature = "alloc")]
+        // SOUND: see above
+        if !unsafe { remit.0.strong() } {
+            return Poll::Pending
+        }
+        if remit.0.is_empty() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+}
@@ -0,0 +1,102 @@
+use core::mem::ManuallyDrop;
+
+use crate::{
+    Generator,
+    GeneratorIterator,
+    Remit,
+};
+
+/// Storage for a generator whose [`Remit`] is branded with the lifetime of borrowing the storage,
+/// instead of the generator being required to accept any lifetime.
+/// Stack based (does not use an allocation).
+///
+/// This allows generators with explicit lifetime bounds, which are not higher-ranked,
+/// such as the `gen_explicit` of the [crate examples](crate#examples).
+/// The storage stays borrowed for as long as the generator could hold the `Remit`,
+/// which is usually the rest of its scope.
+///
+/// The generator is always dropped before [`BrandedStorage::scope()`] returns,
+/// so dropping the storage does not drop anything.
+/// Values remitted through a `Remit` held past the scope are leaked.
+///
+/// ```
+/// # use remit::{BrandedStorage, Remit};
+/// fn gen_explicit<'a: 'c, 'b: 'c, 'c>(data: &'a str, remit: Remit<'b, usize>) -> impl std::future::Future<Output=()> + 'c {
+///     async move {
+///         remit.value(data.len()).await;
+///         remit.value(data.len() * 2).await;
+///     }
+/// }
+/// let data = String::from("hi");
+/// let mut storage = BrandedStorage::new();
+/// assert_eq!(vec![2, 4], storage.scope_with(gen_explicit, &data, |iter| iter.collect::<Vec<_>>()));
+/// ```
+///
+/// A generator that keeps the `Remit` for longer needs the storage for longer.
+/// ```compile_fail
+/// # use std::future::{ready, Ready};
+/// # use remit::{BrandedStorage, Remit};
+/// fn stash(remit: Remit<'static, usize>) -> Ready<()> {
+///     Box::leak(Box::new(remit));
+///     ready(())
+/// }
+/// let mut storage = BrandedStorage::new();
+/// // Fails to compile, because the storage isn't borrowed for `'static`.
+/// storage.scope(stash, |iter| iter.count());
+/// ```
+pub struct BrandedStorage<T, P>(ManuallyDrop<Generator<T, P>>);
+
+impl<T, P> Default for BrandedStorage<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Drops the generator and any values, including when unwinding.
+struct Clear<T, P>(*mut Generator<T, P>);
+
+impl<T, P> Drop for Clear<T, P> {
+    fn drop(&mut self) {
+        // SOUND: the storage is exclusively borrowed by the scope, and the iterator is gone
+        unsafe { (*self.0).clear() };
+    }
+}
+
+impl<T, P> BrandedStorage<T, P> {
+    /// Provides the storage, which does not need to be pinned.
+    pub const fn new() -> Self {
+        BrandedStorage(ManuallyDrop::new(Generator::new()))
+    }
+
+    /// Provides an iterator to the callback, with the generator's `Remit` branded with `'s`.
+    ///
+    /// The iterator's lifetime is unique to the callback, such that it can't escape the scope.
+    /// Otherwise, this is the same as [`Generator::scope()`].
+    pub fn scope<'s, G, R>(
+        &'s mut self,
+        gen: G,
+        f: impl FnOnce(GeneratorIterator<'_, T, P>) -> R,
+    ) -> R
+        where
+            G: FnOnce(Remit<'s, T>) -> P,
+    {
+        self.scope_with(|(), remit| gen(remit), (), f)
+    }
+
+    /// The same as [`BrandedStorage::scope()`] but allows passing a parameter in.
+    pub fn scope_with<'s, G, X, R>(
+        &'s mut self,
+        gen: G,
+        parameter: X,
+        f: impl FnOnce(GeneratorIterator<'_, T, P>) -> R,
+    ) -> R
+        where
+            G: FnOnce(X, Remit<'s, T>) -> P,
+    {
+        let raw: *mut Generator<T, P> = &mut *self.0;
+        let _clear = Clear(raw);
+        // SOUND: the storage is exclusive for 's, and is only pinned until the future is dropped by the guard.
+        // The Remit can't outlive 's, for which the storage is borrowed.
+        f(unsafe { Generator::start(raw, |remit| gen(parameter, remit)) })
+    }
+}
@@ -0,0 +1,271 @@
+use core::{
+    future::Future,
+    mem::{
+        self,
+        ManuallyDrop,
+    },
+    ops::AsyncFnOnce,
+    pin::Pin,
+    ptr::read,
+    task::Poll,
+};
+
+#[cfg(feature = "alloc")]
+use crate::Owner;
+
+use crate::{
+    poll_slot,
+    Generator,
+    GeneratorIterator,
+    Mode,
+    Remit,
+    StaticCall,
+    Storage,
+};
+
+/// The storage of a future, with its type erased.
+trait Slot {
+    /// Must be pinned.
+    unsafe fn poll(&mut self) -> Poll<()>;
+
+    /// Drops the future in place.
+    fn release(&mut self);
+}
+
+impl<P: Future<Output=()>> Slot for Option<P> {
+    unsafe fn poll(&mut self) -> Poll<()> {
+        // SOUND: unsafe-fn, see above
+        poll_slot(self)
+    }
+
+    fn release(&mut self) {
+        *self = None;
+    }
+}
+
+/// An iterator over generated values, with the type of the future erased.
+///
+/// Nameable, such that different generators may be stored together,
+/// kept in struct fields, or returned from trait methods.
+/// Otherwise, this is the same as a [`GeneratorIterator`].
+///
+/// ```
+/// # use std::pin::pin;
+/// # use remit::{DynGeneratorIterator, Generator, Remit};
+/// async fn evens(remit: Remit<'_, usize>) {
+///     for i in 1..=3 {
+///         remit.value(i * 2).await;
+///     }
+/// }
+/// async fn odds(remit: Remit<'_, usize>) {
+///     for i in 0..3 {
+///         remit.value(i * 2 + 1).await;
+///     }
+/// }
+/// let mut first = pin!(Generator::new());
+/// let mut second = pin!(Generator::new());
+/// let iters: [DynGeneratorIterator<'_, usize>; 2] = [
+///     first.as_mut().of_dyn(evens),
+///     second.as_mut().of_dyn(odds),
+/// ];
+/// assert_eq!(
+///     vec![2, 4, 6, 1, 3, 5],
+///     iters.into_iter().flatten().collect::<Vec<_>>(),
+/// );
+/// ```
+pub struct DynGeneratorIterator<'a, T> {
+    done: bool,
+    mode: Mode<'a, T>,
+    future: *mut (dyn Slot + 'a),
+    #[cfg(feature = "alloc")]
+    _owner: Option<Owner<T>>,
+    _storage: Option<Storage>,
+}
+
+impl<'a, T: 'a, P: Future<Output=()> + 'a> GeneratorIterator<'a, T, P> {
+    /// Erases the type of the future, without any additional allocation.
+    pub fn into_dyn(self) -> DynGeneratorIterator<'a, T> {
+        // The storage is instead released by the DynGeneratorIterator.
+        let iter = ManuallyDrop::new(self);
+        DynGeneratorIterator {
+            done: iter.done,
+            mode: iter.mode,
+            future: iter.future,
+            #[cfg(feature = "alloc")]
+            // SOUND: only read once, as the original is never dropped
+            _owner: unsafe { read(&iter._owner) },
+            // SOUND: see above
+            _storage: unsafe { read(&iter._storage) },
+        }
+    }
+}
+
+impl<'a, T: 'a, P: Future<Output=()> + 'a> From<GeneratorIterator<'a, T, P>> for DynGeneratorIterator<'a, T> {
+    fn from(iter: GeneratorIterator<'a, T, P>) -> Self {
+        iter.into_dyn()
+    }
+}
+
+impl<T, P: Future<Output=()>> Generator<T, P> {
+    #[allow(clippy::needless_lifetimes)]
+    /// The same as [`Generator::of()`], but with the type of the future erased.
+    /// Stack based (does not use an allocation).
+    pub fn of_dyn<'s, G>(
+        self: Pin<&'s mut Self>,
+        gen: G,
+    ) -> DynGeneratorIterator<'s, T>
+        where
+            // insures fn is not implemented only for 'static
+            G: for<'a> AsyncFnOnce(Remit<'a, T>),
+            // insures P is properly defined, even if it actually has a lifetime
+            G: StaticCall<(Remit<'static, T>, ), Output=P>,
+    {
+        self.of(gen).into_dyn()
+    }
+
+    #[cfg(feature = "alloc")]
+    /// The same as [`Generator::boxed()`], but with the type of the future erased.
+    ///
+    /// The future is kept in the same single allocation as the boxed generator.
+    ///
+    /// ```
+    /// # use remit::{DynGeneratorIterator, Generator, Remit};
+    /// trait Shape {
+    ///     fn corners(&self) -> DynGeneratorIterator<'_, (i32, i32)>;
+    /// }
+    ///
+    /// struct Square(i32);
+    ///
+    /// impl Shape for Square {
+    ///     fn corners(&self) -> DynGeneratorIterator<'_, (i32, i32)> {
+    ///         Generator::boxed_dyn(|remit| async move {
+    ///             for (x, y) in [(0, 0), (1, 0), (1, 1), (0, 1)] {
+    ///                 remit.value((x * self.0, y * self.0)).await;
+    ///             }
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     vec![(0, 0), (2, 0), (2, 2), (0, 2)],
+    ///     Square(2).corners().collect::<Vec<_>>(),
+    /// );
+    /// ```
+    pub fn boxed_dyn<'a>(gen: impl FnOnce(Remit<'static, T>) -> P) -> DynGeneratorIterator<'a, T>
+        where
+            T: 'a,
+            P: 'a,
+    {
+        Generator::boxed(gen).into_dyn()
+    }
+}
+
+impl<'a, T> DynGeneratorIterator<'a, T> {
+    /// Marks the iterator as [`Send`], when the values are.
+    ///
+    /// # Safety
+    ///
+    /// The future is not checked, as it always holds the non-`Send` [`Remit`].
+    /// The future must otherwise be safe to send to another thread,
+    /// being composed of only `Send` values, and the `Remit` must not have been moved out of it,
+    /// such as by a generator that only accepts `'static`.
+    ///
+    /// ```
+    /// # use std::thread;
+    /// # use remit::{Generator, Remit};
+    /// # #[cfg(feature = "alloc")] {
+    /// async fn gen(remit: Remit<'_, usize>) {
+    ///     remit.value(1).await;
+    ///     remit.value(2).await;
+    /// }
+    /// // SAFETY: gen only holds its Remit, and can't move it out as it accepts any lifetime.
+    /// let iter = unsafe { Generator::boxed_dyn(gen).into_send() };
+    /// let values = thread::spawn(move || iter.collect::<Vec<_>>()).join().unwrap();
+    /// assert_eq!(vec![1, 2], values);
+    /// # }
+    /// ```
+    pub unsafe fn into_send(self) -> DynSendGeneratorIterator<'a, T> {
+        DynSendGeneratorIterator(self)
+    }
+}
+
+impl<T> DynGeneratorIterator<'_, T> {
+    /// See [`GeneratorIterator::detach()`].
+    pub fn detach(self) {
+        if self.mode.is_pinned() && self._storage.is_none() {
+            // The storage is left as-is, for a later iterator to resume from.
+            // Nothing is owned by the iterator itself.
+            mem::forget(self);
+        }
+    }
+}
+
+impl<T> Drop for DynGeneratorIterator<'_, T> {
+    fn drop(&mut self) {
+        if self.mode.is_pinned() {
+            self.mode.close();
+            // SOUND: see GeneratorIterator::drop()
+            unsafe { (*self.future).release() };
+            self.mode.clear();
+        }
+    }
+}
+
+impl<T> Iterator for DynGeneratorIterator<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if let Some(value) = self.mode.next() {
+            return Some(value)
+        }
+        if self.done {
+            return None
+        }
+        // SOUND: see GeneratorIterator::next(), as the ptr was taken from one
+        if let Poll::Ready(()) = unsafe { (*self.future).poll() } {
+            self.done = true;
+            self.mode.finish();
+        }
+        self.mode.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.mode.len();
+        if self.done {
+            (len, Some(len))
+        } else {
+            (len, None)
+        }
+    }
+}
+
+/// A [`DynGeneratorIterator`] that is [`Send`] when the values are.
+///
+/// Created with [`DynGeneratorIterator::into_send()`].
+pub struct DynSendGeneratorIterator<'a, T>(DynGeneratorIterator<'a, T>);
+
+// SOUND: unsafe-fn DynGeneratorIterator::into_send().
+// The future, storage, and Remit are only reachable through the iterator,
+// either owned in _owner or exclusively borrowed pinned-storage.
+unsafe impl<T: Send> Send for DynSendGeneratorIterator<'_, T> {}
+
+impl<'a, T> DynSendGeneratorIterator<'a, T> {
+    /// Provides the iterator that is not `Send`.
+    pub fn into_inner(self) -> DynGeneratorIterator<'a, T> {
+        self.0
+    }
+}
+
+impl<T> Iterator for DynSendGeneratorIterator<'_, T> {
+    type Item = T;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<T> {
+        self.0.next()
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
@@ -0,0 +1,150 @@
+//! Lazy formatting of remitted values.
+//!
+//! The generator is re-run for every formatting, using pinned storage,
+//! so nothing is allocated by the formatting itself.
+//! Each remitted value is written with its [`Display`](core::fmt::Display) implementation,
+//! ignoring the flags of the formatter.
+//!
+//! ```
+//! # use std::fmt::Display;
+//! # use remit::Remit;
+//! # use remit::fmt::display_with;
+//! struct Order {
+//!     items: Vec<(&'static str, u32)>,
+//!     note: Option<String>,
+//! }
+//!
+//! async fn summary<'a>(order: &'a Order, remit: Remit<'_, &'a dyn Display>) {
+//!     for (index, (name, count)) in order.items.iter().enumerate() {
+//!         if index > 0 {
+//!             remit.value(&", ").await;
+//!         }
+//!         remit.value(count).await;
+//!         remit.value(&" ").await;
+//!         remit.value(name).await;
+//!     }
+//!     if let Some(note) = &order.note {
+//!         remit.value(&" (").await;
+//!         remit.value(note).await;
+//!         remit.value(&")").await;
+//!     }
+//! }
+//!
+//! let order = Order {
+//!     items: vec![("apples", 3), ("pears", 5)],
+//!     note: Some("ripe".to_string()),
+//! };
+//! assert_eq!("3 apples, 5 pears (ripe)", display_with(summary, &order).to_string());
+//! ```
+
+use core::{
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    ops::AsyncFnOnce,
+    pin::pin,
+};
+
+use crate::{
+    Generator,
+    Remit,
+    StaticCall,
+};
+
+/// Provides [`Display`](fmt::Display) and [`Debug`](fmt::Debug) by writing the values remitted by a generator.
+///
+/// The generator must be [`Copy`], such as an `async fn`, as it is re-run each time.
+pub struct Formatted<G, T, X = ()> {
+    gen: G,
+    parameter: X,
+    _item: PhantomData<fn() -> T>,
+}
+
+/// Formats the values remitted by the generator.
+///
+/// ```
+/// # use remit::Remit;
+/// # use remit::fmt::display;
+/// async fn countdown(remit: Remit<'_, usize>) {
+///     for i in (1..=3).rev() {
+///         remit.value(i).await;
+///     }
+/// }
+/// assert_eq!("321", format!("{}", display(countdown)));
+/// ```
+pub fn display<G, T, P>(gen: G) -> Formatted<G, T>
+    where
+        G: Copy,
+        G: for<'a> AsyncFnOnce(Remit<'a, T>),
+        G: StaticCall<(Remit<'static, T>, ), Output=P>,
+        P: Future<Output=()>,
+        T: fmt::Display,
+{
+    Formatted {
+        gen,
+        parameter: (),
+        _item: PhantomData,
+    }
+}
+
+/// Formats the values remitted by the generator, which is passed a clone of the parameter each time.
+///
+/// See [`Generator::parameterized()`].
+pub fn display_with<G, T, X, P>(gen: G, parameter: X) -> Formatted<G, T, (X, )>
+    where
+        G: Copy,
+        G: for<'a> AsyncFnOnce(X, Remit<'a, T>),
+        G: StaticCall<(X, Remit<'static, T>, ), Output=P>,
+        P: Future<Output=()>,
+        T: fmt::Display,
+        X: Clone,
+{
+    Formatted {
+        gen,
+        parameter: (parameter, ),
+        _item: PhantomData,
+    }
+}
+
+fn write_all<T: fmt::Display>(f: &mut fmt::Formatter<'_>, iter: impl Iterator<Item=T>) -> fmt::Result {
+    for item in iter {
+        write!(f, "{item}")?;
+    }
+    Ok(())
+}
+
+impl<G, T, P> fmt::Display for Formatted<G, T>
+    where
+        G: Copy,
+        G: for<'a> AsyncFnOnce(Remit<'a, T>),
+        G: StaticCall<(Remit<'static, T>, ), Output=P>,
+        P: Future<Output=()>,
+        T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_all(f, pin!(Generator::new()).of(self.gen))
+    }
+}
+
+impl<G, T, X, P> fmt::Display for Formatted<G, T, (X, )>
+    where
+        G: Copy,
+        G: for<'a> AsyncFnOnce(X, Remit<'a, T>),
+        G: StaticCall<(X, Remit<'static, T>, ), Output=P>,
+        P: Future<Output=()>,
+        T: fmt::Display,
+        X: Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_all(f, pin!(Generator::new()).parameterized(self.gen, self.parameter.0.clone()))
+    }
+}
+
+impl<G, T, X> fmt::Debug for Formatted<G, T, X>
+    where
+        Self: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
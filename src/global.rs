@@ -0,0 +1,135 @@
+use core::{
+    cell::UnsafeCell,
+    ops::AsyncFnOnce,
+    sync::atomic::{
+        AtomicBool,
+        Ordering,
+    },
+};
+
+use crate::{
+    Generator,
+    GeneratorIterator,
+    Remit,
+    StaticCall,
+};
+
+/// Storage for a generator that may be kept in a `static`.
+/// Does not use an allocation.
+///
+/// Starting the generator provides an iterator with a `'static` lifetime,
+/// which may be returned or stored globally, like [`Generator::boxed()`].
+/// The storage may only be started once; later attempts provide `None`.
+///
+/// The future must be nameable, such as a hand-written future,
+/// or an `async fn` with the `type_alias_impl_trait` nightly feature.
+///
+/// As a `static` is shared between threads, the values and the future must be [`Send`].
+/// The [`Remit`] is not `Send`, so a future holding it must assert that it is,
+/// which is sound as long as the future is only ever polled by the iterator that started it.
+///
+/// ```
+/// # use std::{future::Future, pin::Pin, task::{Context, Poll}};
+/// # use remit::{GeneratorIterator, Remit, StaticGenerator};
+/// struct Countdown<'a>(Remit<'a, u16>, u16);
+///
+/// // SAFETY: only ever polled by the iterator, on the thread that started it
+/// unsafe impl Send for Countdown<'_> {}
+///
+/// impl Future for Countdown<'_> {
+///     type Output = ();
+///
+///     fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+///         if self.1 == 0 {
+///             return Poll::Ready(())
+///         }
+///         // Remitted eagerly, as the iterator consumes it before polling again.
+///         drop(self.0.value(self.1));
+///         self.1 -= 1;
+///         Poll::Pending
+///     }
+/// }
+///
+/// fn countdown(remit: Remit<'_, u16>) -> Countdown<'_> {
+///     Countdown(remit, 3)
+/// }
+///
+/// static GEN: StaticGenerator<u16, Countdown<'static>> = StaticGenerator::new();
+///
+/// fn values() -> GeneratorIterator<'static, u16, Countdown<'static>> {
+///     GEN.of(countdown).expect("only started once")
+/// }
+///
+/// let iter = values();
+/// assert!(GEN.is_started());
+/// assert!(GEN.of(countdown).is_none());
+/// assert_eq!(vec![3, 2, 1], iter.collect::<Vec<_>>());
+/// ```
+///
+/// A generator holding values that can't be sent, such as an `Rc`, can't be kept in a `static`.
+/// ```compile_fail
+/// # use std::{future::Future, pin::Pin, rc::Rc, task::{Context, Poll}};
+/// # use remit::StaticGenerator;
+/// struct Shared(Rc<u16>);
+///
+/// impl Future for Shared {
+///     type Output = ();
+///
+///     fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+///         Poll::Ready(())
+///     }
+/// }
+///
+/// // Fails to compile, because the future isn't `Send`.
+/// static GEN: StaticGenerator<u16, Shared> = StaticGenerator::new();
+/// ```
+pub struct StaticGenerator<T, P> {
+    started: AtomicBool,
+    storage: UnsafeCell<Generator<T, P>>,
+}
+
+// SOUND: the storage is only ever accessed by the single caller that started it,
+// through the iterator, which is neither Send nor Sync.
+// The values and future are created by that caller, and may be sent from its thread.
+unsafe impl<T: Send, P: Send> Sync for StaticGenerator<T, P> {}
+
+impl<T, P> Default for StaticGenerator<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, P> StaticGenerator<T, P> {
+    /// Provides the storage, usable to initialize a `static`.
+    pub const fn new() -> Self {
+        StaticGenerator {
+            started: AtomicBool::new(false),
+            storage: UnsafeCell::new(Generator::new()),
+        }
+    }
+
+    /// Indicates that the generator has been started.
+    pub fn is_started(&self) -> bool {
+        self.started.load(Ordering::Acquire)
+    }
+
+    /// Starts the generator, providing an iterator if not already started.
+    ///
+    /// The same lifetime relaxation as [`Generator::of()`] applies,
+    /// such that the generator must accept any lifetime.
+    pub fn of<G>(&'static self, gen: G) -> Option<GeneratorIterator<'static, T, P>>
+        where
+            // insures fn is not implemented only for 'static
+            G: for<'a> AsyncFnOnce(Remit<'a, T>),
+            // insures P is properly defined, even if it actually has a lifetime
+            G: StaticCall<(Remit<'static, T>, ), Output=P>,
+    {
+        if self.started.swap(true, Ordering::AcqRel) {
+            return None
+        }
+        // SOUND: the storage is static and never moved,
+        // and exclusive, as only the first caller gets here.
+        // The trait-guard is where the lifetime protection is.
+        Some(unsafe { Generator::start(self.storage.get(), |remit| gen.call_static((remit, ))) })
+    }
+}
@@ -0,0 +1,159 @@
+//! Adapters between generators and [`std::io`].
+//!
+//! A [`Reader`] provides the chunks remitted by a generator as a [`Read`] and [`BufRead`],
+//! and a [`Writer`] feeds the bytes written to it into a [`Consumer`].
+//!
+//! ```
+//! # use std::io::{self, Read};
+//! # use remit::{Generator, Receive, Remit};
+//! # use remit::io::{Reader, Writer};
+//! async fn csv(rows: &[(&str, usize)], remit: Remit<'static, Vec<u8>>) {
+//!     remit.value(b"name,count\n".to_vec()).await;
+//!     for (name, count) in rows {
+//!         remit.value(format!("{name},{count}\n").into_bytes()).await;
+//!     }
+//! }
+//! async fn count_lines(input: Receive<'static, Vec<u8>>) -> usize {
+//!     let mut lines = 0;
+//!     while let Some(chunk) = input.next().await {
+//!         lines += chunk.iter().filter(|&&byte| byte == b'\n').count();
+//!     }
+//!     lines
+//! }
+//! let rows = [("apples", 3), ("pears", 5)];
+//! let mut reader = Reader::new(Generator::boxed(|remit| csv(&rows, remit)));
+//! let mut writer = Writer::new(Generator::boxed_consumer(count_lines));
+//! io::copy(&mut reader, &mut writer).unwrap();
+//! assert_eq!(Some(3), writer.finish());
+//! ```
+
+use core::future::Future;
+
+use std::io::{
+    self,
+    BufRead,
+    Read,
+    Write,
+};
+
+use crate::Consumer;
+
+/// Provides the chunks of an iterator, such as a [`GeneratorIterator`](crate::GeneratorIterator),
+/// as a byte stream.
+///
+/// Chunks are only pulled from the iterator once the previous chunk has been fully read.
+/// Reading provides end-of-file whenever the iterator returns `None`.
+pub struct Reader<I: Iterator> {
+    iter: I,
+    chunk: Option<I::Item>,
+    position: usize,
+}
+
+impl<I> Reader<I>
+    where
+        I: Iterator,
+        I::Item: AsRef<[u8]>,
+{
+    /// Wraps the iterator of chunks.
+    pub fn new(iter: I) -> Self {
+        Reader {
+            iter,
+            chunk: None,
+            position: 0,
+        }
+    }
+
+    /// Provides the iterator, discarding any partially read chunk.
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
+}
+
+impl<I> BufRead for Reader<I>
+    where
+        I: Iterator,
+        I::Item: AsRef<[u8]>,
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.chunk.as_ref().is_none_or(|chunk| self.position >= chunk.as_ref().len()) {
+            self.position = 0;
+            self.chunk = self.iter.next();
+            if self.chunk.is_none() {
+                return Ok(&[])
+            }
+        }
+        match &self.chunk {
+            Some(chunk) => Ok(&chunk.as_ref()[self.position..]),
+            None => Ok(&[]),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position += amt;
+    }
+}
+
+impl<I> Read for Reader<I>
+    where
+        I: Iterator,
+        I::Item: AsRef<[u8]>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+/// Feeds the bytes written to it into a [`Consumer`], as chunks.
+///
+/// Each write is fed as a single chunk.
+/// Writing after the consumer's generator has finished fails with [`io::ErrorKind::BrokenPipe`].
+pub struct Writer<'a, C, P: Future> {
+    consumer: Consumer<'a, C, P>,
+}
+
+impl<'a, C, P> Writer<'a, C, P>
+    where
+        C: for<'b> From<&'b [u8]>,
+        P: Future,
+{
+    /// Wraps the consumer.
+    pub fn new(consumer: Consumer<'a, C, P>) -> Self {
+        Writer {
+            consumer,
+        }
+    }
+
+    /// Signals the end of input to the consumer, and provides its output.
+    ///
+    /// See [`Consumer::finish()`].
+    pub fn finish(self) -> Option<P::Output> {
+        self.consumer.finish()
+    }
+
+    /// Provides the consumer, without finishing it.
+    pub fn into_inner(self) -> Consumer<'a, C, P> {
+        self.consumer
+    }
+}
+
+impl<C, P> Write for Writer<'_, C, P>
+    where
+        C: for<'b> From<&'b [u8]>,
+        P: Future,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.consumer.is_done() {
+            return Err(io::ErrorKind::BrokenPipe.into())
+        }
+        self.consumer.feed(buf.into());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
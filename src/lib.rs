@@ -29,8 +29,15 @@
 //!     // Prints 42, 1, 2, 3, 4, 5, 6, 7, 8, 9
 //! }
 //! assert_eq!(vec![42, 1, 2, 3], pin!(Generator::new()).of(gen).take(4).collect::<Vec<_>>());
+//! // Async closures can be used directly
+//! assert_eq!(
+//!     vec![1],
+//!     pin!(Generator::new())
+//!         .of(async |remit| { remit.value(1).await; })
+//!         .collect::<Vec<_>>(),
+//! );
 //! /*
-//! // Rust has trouble determining the lifetime
+//! // Rust has trouble determining the lifetime of a closure returning an async block
 //! assert_eq!(
 //!     vec![1],
 //!     pin!(Generator::new())
@@ -189,7 +196,15 @@
 //!     dbg!(item);
 //! }
 //!
-//! /// Does not work, as explicit lifetime definitions fail HRTB.
+//! // Async closures can instead capture what they borrow
+//! for item in pin!(Generator::new()).of(async |remit| {
+//!     remit.value(data.len()).await;
+//!     remit.value(data.len()).await;
+//! }) {
+//!     dbg!(item);
+//! }
+//!
+//! /// Does not work with the pinned storage directly, as explicit lifetime definitions fail HRTB.
 //! fn gen_explicit<'a: 'c, 'b: 'c, 'c>(data: &'a str, remit: Remit<'b, usize>) -> impl std::future::Future<Output=()> + 'c {
 //!     async move {
 //!         remit.value(data.len()).await;
@@ -201,6 +216,34 @@
 //!     dbg!(item);
 //! }
 //! */
+//! // Wrapping with an async closure provides the lifetimes, and a scope avoids the pin.
+//! Generator::scope_with(async |data, remit| gen_explicit(data, remit).await, &data, |iter| {
+//!     for item in iter {
+//!         dbg!(item);
+//!     }
+//! });
+//! // Branded storage accepts it directly, as the Remit only needs the lifetime of borrowing the storage.
+//! BrandedStorage::new().scope_with(gen_explicit, &data, |iter| {
+//!     for item in iter {
+//!         dbg!(item);
+//!     }
+//! });
+//! ```
+//!
+//! Helpers may remit without a `Remit` parameter, once the generator is made ambient.
+//! ```
+//! # use std::pin::pin;
+//! # use remit::{yield_value, Generator, Remit};
+//! async fn helper(data: &str) {
+//!     yield_value(data.len()).await;
+//! }
+//! async fn gen(remit: Remit<'_, usize>) {
+//!     remit.ambient(async {
+//!         helper("ab").await;
+//!         helper("abc").await;
+//!     }).await
+//! }
+//! assert_eq!(vec![2, 3], pin!(Generator::new()).of(gen).collect::<Vec<_>>());
 //! ```
 //!
 //! Incorrect attempt of a stack-based generator.
@@ -217,14 +260,52 @@
 //! }
 //! ```
 //!
+//! ## Modules
+//!
+//! * [`parse`] -
+//!   Incremental parsing of input received in chunks.
+//!   Requires `alloc`.
+//! * [`fmt`] -
+//!   Lazy formatting of remitted values.
+//! * [`io`] -
+//!   Reading from generators and writing to consumers.
+//!   Requires `std`.
+//! * [`serde`] -
+//!   Serialization of remitted values.
+//!   Requires `serde`.
+//! * [`thread`] -
+//!   Generators run on dedicated threads, including fanning out to several.
+//!   Requires `std`.
+//! * [`sync`] -
+//!   Generators consumed from an interrupt handler or another core.
+//!   Requires `critical-section`.
+//!
 //! ## Features
 //!
 //! * **alloc** -
 //!   Enables the use of a boxed generator and multiple pending values.
 //!   Defaults to enabled.
+//! * **std** -
+//!   Enables the [`io`] adapters and [`thread`] generators.
+//!   Implies `alloc`.
+//! * **serde** -
+//!   Enables the [`serde`] sequences.
+//! * **allocator-api2** -
+//!   Enables [`Generator::boxed_in()`], for boxed generators in a custom allocator.
+//!   Implies `alloc`.
+//!   The nightly `Allocator` is used when the `nightly` feature of `allocator-api2` is enabled.
+//! * **critical-section** -
+//!   Enables the [`sync`] generators.
+//! * **stackful** -
+//!   Enables [`Generator::stackful()`], for generators on a separate stack that remit without awaiting.
+//!   Implies `std`, and is currently only available for x86-64 Linux.
+//!   On any other target, the feature provides nothing.
 
 use core::{
-    cell::UnsafeCell,
+    cell::{
+        Cell,
+        UnsafeCell,
+    },
     future::{
         Future,
         poll_fn,
@@ -235,7 +316,11 @@ use core::{
         PhantomPinned,
     },
     mem,
-    pin::Pin,
+    ops::AsyncFnOnce,
+    pin::{
+        Pin,
+        pin,
+    },
     task::{
         Poll,
         Context,
@@ -247,78 +332,453 @@ use core::{
 extern crate alloc;
 
 #[cfg(feature = "alloc")]
-use core::{
-    cell::Cell,
-    mem::MaybeUninit,
-    ptr::{
-        addr_of,
-        read,
-        null_mut,
-    },
-};
+use core::ptr::addr_of;
 
 #[cfg(feature = "alloc")]
 use alloc::{
+    boxed::Box,
     collections::VecDeque,
-    rc::{
-        Rc,
-        Weak,
-    },
 };
 
+#[cfg(feature = "allocator-api2")]
+mod allocator;
+mod ambient;
+mod branded;
 mod context;
+mod erased;
+#[cfg(all(feature = "stackful", target_os = "linux", target_arch = "x86_64"))]
+mod stackful;
+#[cfg(target_has_atomic = "8")]
+mod global;
+mod pool;
+mod stack;
+mod storage;
+mod transducer;
+
+#[cfg(feature = "alloc")]
+pub mod parse;
+
+pub mod fmt;
+
+#[cfg(feature = "std")]
+pub mod io;
 
-/// Erases the return-type so that other parameters don't get polluted by the HRTB.
-trait AsyncFnOnce<Arg> {}
+#[cfg(feature = "std")]
+pub mod thread;
 
-impl<F, A, R: Future> AsyncFnOnce<(A, )> for F
-    where
-        F: FnOnce(A) -> R,
-{}
+#[cfg(feature = "serde")]
+pub mod serde;
 
-impl<F, A, B, R: Future> AsyncFnOnce<(A, B, )> for F
-    where
-        F: FnOnce(A, B) -> R,
-{}
+#[cfg(feature = "critical-section")]
+pub mod sync;
+
+pub use ambient::yield_value;
+
+pub use branded::BrandedStorage;
+
+pub use erased::{
+    DynGeneratorIterator,
+    DynSendGeneratorIterator,
+};
+
+#[cfg(target_has_atomic = "8")]
+pub use global::StaticGenerator;
+
+pub use stack::StackGenerator;
+
+#[cfg(all(feature = "stackful", target_os = "linux", target_arch = "x86_64"))]
+pub use stackful::{
+    Stackful,
+    STACKFUL_STACK_SIZE,
+};
+
+#[cfg(feature = "alloc")]
+pub use storage::BoxHandle;
+pub use storage::{
+    GeneratorStorage,
+    StorageHandle,
+};
+
+pub use pool::{
+    GeneratorPool,
+    PoolSlot,
+};
+
+use storage::Storage;
+
+pub use transducer::{
+    Transducer,
+    Transduction,
+};
+
+#[cfg(feature = "alloc")]
+pub use transducer::{
+    Transduce,
+    Transduced,
+};
 
 /// Trait used for relaxing the lifetime requirements of the generator storage.
 ///
-/// Implemented automatically for generators that accept any lifetime.
+/// Implemented automatically for generators that accept any lifetime,
+/// with up to 8 parameters before the [`Remit`].
 ///
 /// Direct usage of this trait is not considered part of SemVer.
+/// Outside of [`Generator::parameterized_tuple()`],
+/// the storage directly requires [`AsyncFnOnce`] for any lifetime instead.
+///
+/// # Safety
+///
+/// Implementations must only exist for functions that accept a [`Remit`] of any lifetime.
 pub unsafe trait RemitWithLifetime<T, X> {}
 
-unsafe impl<T, F> RemitWithLifetime<T, ()> for F
-    where
-        F: for<'a> AsyncFnOnce<(Remit<'a, T>, )>,
-{}
+/// Trait used for naming the future of a generator when provided the `'static` lifetime.
+///
+/// Implemented automatically for all functions.
+/// Unlike a bound of `FnOnce`, this does not restrict the inferred signature of a closure to `'static`.
+///
+/// Direct usage of this trait is not considered part of SemVer.
+pub trait StaticCall<Args> {
+    /// The returned future.
+    type Output;
 
-unsafe impl<T, X, F> RemitWithLifetime<T, (X, )> for F
-    where
-        F: for<'a> AsyncFnOnce<(X, Remit<'a, T>, )>,
-{}
+    #[doc(hidden)]
+    fn call_static(self, args: Args) -> Self::Output;
+}
+
+/// Trait used for appending the last argument, such as a [`Remit`], to the parameters of a generator.
+///
+/// Implemented for tuples of up to 8 parameters.
+///
+/// Direct usage of this trait is not considered part of SemVer.
+pub trait Parameters<L> {
+    /// The parameters, followed by the last argument.
+    type With;
+
+    #[doc(hidden)]
+    fn with(self, last: L) -> Self::With;
+}
+
+macro_rules! parameters {
+    ($($name:ident)*) => {
+        impl<F, $($name, )* L, R> StaticCall<($($name, )* L, )> for F
+            where
+                F: FnOnce($($name, )* L) -> R,
+        {
+            type Output = R;
+
+            #[inline(always)]
+            #[allow(non_snake_case)]
+            fn call_static(self, ($($name, )* last, ): ($($name, )* L, )) -> R {
+                self($($name, )* last)
+            }
+        }
+
+        unsafe impl<T, $($name, )* F> RemitWithLifetime<T, ($($name, )*)> for F
+            where
+                F: for<'a> AsyncFnOnce($($name, )* Remit<'a, T>),
+        {}
+
+        impl<$($name, )* L> Parameters<L> for ($($name, )*) {
+            type With = ($($name, )* L, );
+
+            #[inline(always)]
+            #[allow(non_snake_case)]
+            fn with(self, last: L) -> Self::With {
+                let ($($name, )*) = self;
+                ($($name, )* last, )
+            }
+        }
+    };
+}
+
+parameters!();
+parameters!(A);
+parameters!(A B);
+parameters!(A B C);
+parameters!(A B C D);
+parameters!(A B C D E);
+parameters!(A B C D E F2);
+parameters!(A B C D E F2 G);
+parameters!(A B C D E F2 G H);
+
+/// A type that can be used as a generator, such as a domain type providing its contents.
+///
+/// The same as an `async fn` accepting a [`Remit`], but nameable, and implemented on a type.
+/// Use [`Generate::scope()`] or [`Generate::boxed()`] for an iterator,
+/// or [`Generate::into_fn()`] to provide it to any of the storage.
+///
+/// `IntoIterator` cannot be implemented for all implementors, as the trait belongs to `core`.
+///
+/// ```
+/// # use std::pin::pin;
+/// # use remit::{Generate, Generator, Remit};
+/// struct Node {
+///     value: usize,
+///     children: Vec<Node>,
+/// }
+///
+/// impl Generate for &Node {
+///     type Item = usize;
+///     type Output = ();
+///
+///     async fn generate(self, remit: Remit<'_, usize>) {
+///         let mut stack = vec![self];
+///         while let Some(node) = stack.pop() {
+///             remit.value(node.value).await;
+///             stack.extend(node.children.iter().rev());
+///         }
+///     }
+/// }
+///
+/// let tree = Node {
+///     value: 1,
+///     children: vec![
+///         Node { value: 2, children: vec![Node { value: 3, children: vec![] }] },
+///         Node { value: 4, children: vec![] },
+///     ],
+/// };
+/// assert_eq!(vec![1, 2, 3, 4], pin!(Generator::new()).of(tree.into_fn()).collect::<Vec<_>>());
+/// assert_eq!(10, Generator::scope(tree.into_fn(), |iter| iter.sum::<usize>()));
+/// assert_eq!(10, tree.scope(|iter| iter.sum::<usize>()));
+/// # #[cfg(feature = "alloc")]
+/// assert_eq!(vec![1, 2, 3, 4], tree.boxed().collect::<Vec<_>>());
+/// ```
+pub trait Generate {
+    /// The type of value remitted.
+    type Item;
+    /// The type returned when finished.
+    type Output;
+
+    /// Remits the values of the generator.
+    fn generate(self, remit: Remit<'_, Self::Item>) -> impl Future<Output=Self::Output>;
+
+    #[allow(clippy::type_complexity)]
+    /// Provides the generator as an async closure,
+    /// such that it may be used with [`Generator::of()`] or [`Generator::scope()`].
+    fn into_fn(self) -> impl for<'a> AsyncFnOnce(Remit<'a, Self::Item>) -> Self::Output
+        + StaticCall<(Remit<'static, Self::Item>, ), Output: Future<Output=Self::Output>>
+        where
+            Self: Sized,
+    {
+        async move |remit: Remit<'_, Self::Item>| self.generate(remit).await
+    }
+
+    /// Provides an iterator to the callback, using pinned storage owned by the scope.
+    /// Stack based (does not use an allocation).
+    ///
+    /// The type of the future can't be named by the callback,
+    /// so it is erased, the same as [`GeneratorIterator::into_dyn()`].
+    /// Otherwise, this is the same as [`Generator::scope()`].
+    fn scope<R>(self, f: impl FnOnce(DynGeneratorIterator<'_, Self::Item>) -> R) -> R
+        where
+            Self: Generate<Output=()> + Sized,
+    {
+        Generator::scope(self.into_fn(), |iter| f(iter.into_dyn()))
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Provides an iterator, using an allocation.
+    ///
+    /// See [`Generator::boxed()`].
+    fn boxed(self) -> GeneratorIterator<'static, Self::Item, impl Future<Output=Self::Output>>
+        where
+            Self: Sized,
+    {
+        Generator::boxed(|remit| self.generate(remit))
+    }
+}
+
+/// The largest [storage](Generator::STORAGE_SIZE) kept inline by [`Generator::auto()`].
+///
+/// Use [`Generator::auto_with_limit()`] for a different limit.
+pub const AUTO_INLINE_LIMIT: usize = 2048;
 
 /// The storage used for iterators that poll a generator.
 pub struct Generator<T, P> {
-    values: UnsafeCell<Values<T>>,
+    // NEED: dropped before the exchange, as the future may remit into it while dropping
     future: Option<P>,
+    exchange: Exchange<T>,
     _pin: PhantomPinned,
 }
 
+impl<T, P> Default for Generator<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T, P> Generator<T, P> {
     /// Provides the storage to be pinned when not using an allocation.
-    pub fn new() -> Self {
+    ///
+    /// Usable in a const context, such as for a [`StaticGenerator`].
+    pub const fn new() -> Self {
         Generator {
-            values: UnsafeCell::new(Values::Missing),
             future: None,
+            exchange: Exchange::new(),
             _pin: PhantomPinned,
         }
     }
 
+    #[allow(clippy::needless_lifetimes)]
+    /// Provides an iterator for the generator already in the storage,
+    /// continuing from where a [detached](GeneratorIterator::detach()) iterator left off.
+    ///
+    /// Values that were remitted but not consumed are provided first.
+    /// If the storage is not [running](Generator::is_running()), only those values are provided.
+    ///
+    /// ```
+    /// # use std::pin::pin;
+    /// # use remit::{Generator, Remit};
+    /// async fn gen(remit: Remit<'_, usize>) {
+    ///     for i in 1..=5 {
+    ///         remit.value(i).await;
+    ///     }
+    /// }
+    /// let mut storage = pin!(Generator::new());
+    /// let mut iter = storage.as_mut().of(gen);
+    /// assert_eq!(Some(1), iter.next());
+    /// assert_eq!(Some(2), iter.next());
+    /// iter.detach();
+    ///
+    /// // The storage may be kept between frames, or held across requests.
+    /// assert!(storage.is_running());
+    /// let mut iter = storage.as_mut().resume_iter();
+    /// assert_eq!(Some(3), iter.next());
+    /// iter.detach();
+    /// assert_eq!(vec![4, 5], storage.as_mut().resume_iter().collect::<Vec<_>>());
+    /// // Dropping the iterator released the future.
+    /// assert!(!storage.is_running());
+    /// ```
+    pub fn resume_iter<'s>(self: Pin<&'s mut Self>) -> GeneratorIterator<'s, T, P> {
+        // SOUND: Pin passthrough; only `future` is inner-pinned.
+        // `future` only ever gets replaced via Option::insert, or dropped in place
+        let inner = unsafe { self.get_unchecked_mut() };
+        let mode = Mode::Pinned {
+            exchange: &inner.exchange,
+            // This becomes 'static, and the trait-guard when started is where the real protection is
+            _lifetime: PhantomData,
+        };
+        GeneratorIterator {
+            done: inner.exchange.finished.get() || inner.future.is_none(),
+            mode,
+            future: &mut inner.future,
+            #[cfg(feature = "alloc")]
+            _owner: None,
+            _storage: None,
+        }
+    }
+
+    /// Drops the future and any values that were not consumed,
+    /// such that the storage is idle.
+    ///
+    /// This is also done when starting another generator in the storage,
+    /// which allows the same pinned storage to be reused for many successive runs,
+    /// and when dropping an iterator that was not [detached](GeneratorIterator::detach()).
+    ///
+    /// ```
+    /// # use std::pin::pin;
+    /// # use remit::{Generator, Remit};
+    /// async fn frame(index: usize, remit: Remit<'_, usize>) {
+    ///     remit.value(index).await;
+    ///     remit.value(index * 10).await;
+    /// }
+    /// let mut storage = pin!(Generator::new());
+    /// for index in 1..=1000 {
+    ///     let mut iter = storage.as_mut().parameterized(frame, index);
+    ///     // Only the first value is consumed; the rest are dropped with the next run.
+    ///     assert_eq!(Some(index), iter.next());
+    ///     iter.detach();
+    ///     assert!(storage.is_running());
+    /// }
+    /// storage.as_mut().reset();
+    /// assert!(!storage.is_running());
+    /// ```
+    pub fn reset(self: Pin<&mut Self>) {
+        // SOUND: Pin passthrough; only `future` is inner-pinned, and dropped in place
+        unsafe { self.get_unchecked_mut() }.clear();
+    }
+
+    /// Indicates that the storage holds a future, which may have finished.
+    ///
+    /// The future is held from when a generator is started,
+    /// until the storage is [reset](Generator::reset()) or the future is [taken](Generator::take_future()).
+    pub fn is_running(&self) -> bool {
+        self.future.is_some()
+    }
+
+    /// Drops the future early, keeping any values that were not consumed.
+    /// Provides `true` iff there was a future.
+    ///
+    /// The future is never moved out, as it refers to the storage.
+    ///
+    /// ```
+    /// # use std::pin::pin;
+    /// # use remit::{Generator, Remit};
+    /// async fn gen(remit: Remit<'_, usize>) {
+    ///     for i in 1.. {
+    ///         remit.value(i).await;
+    ///     }
+    /// }
+    /// let mut storage = pin!(Generator::new());
+    /// let mut iter = storage.as_mut().of(gen);
+    /// assert_eq!(Some(1), iter.next());
+    /// iter.detach();
+    /// assert!(storage.as_mut().take_future());
+    /// assert!(!storage.is_running());
+    /// assert!(!storage.as_mut().take_future());
+    /// // Nothing remains to be resumed.
+    /// assert_eq!(None, storage.as_mut().resume_iter().next());
+    /// ```
+    pub fn take_future(self: Pin<&mut Self>) -> bool {
+        // SOUND: Pin passthrough; only `future` is inner-pinned, and dropped in place
+        let inner = unsafe { self.get_unchecked_mut() };
+        let running = inner.future.is_some();
+        // Note that Option::take would move the future before dropping it.
+        inner.future = None;
+        running
+    }
+
+    /// Starts the generator in the storage, dropping any previous future and values.
+    ///
+    /// # Safety
+    ///
+    /// The storage must be pinned and exclusive for `'s`,
+    /// and the generator must be valid for any lifetime provided to the `Remit`.
+    unsafe fn start<'s>(raw: *mut Self, gen: impl FnOnce(Remit<'static, T>) -> P) -> GeneratorIterator<'s, T, P> {
+        // SOUND: unsafe-fn, see above.
+        // `future` only ever gets replaced via Option::insert, or dropped in place
+        let inner = unsafe { &mut *raw };
+        inner.clear();
+        let mode = Mode::Pinned {
+            exchange: &inner.exchange,
+            // This becomes 'static, and the caller's trait-guard is where the real protection is
+            _lifetime: PhantomData,
+        };
+        inner.future = Some(gen(Remit(mode)));
+        GeneratorIterator {
+            done: false,
+            mode,
+            future: &mut inner.future,
+            #[cfg(feature = "alloc")]
+            _owner: None,
+            _storage: None,
+        }
+    }
+
+    /// Drops the future in place, followed by any values, and re-opens the exchange.
+    fn clear(&mut self) {
+        self.future = None;
+        *self.exchange.values.get_mut() = Values::Missing;
+        self.exchange.closed.set(false);
+        self.exchange.finished.set(false);
+    }
+
     #[allow(clippy::needless_lifetimes)]
     /// Takes the pinned storage and the generator and provides an iterator.
     /// Stack based (does not use an allocation).
     ///
+    /// The generator may be an `async fn` or an async closure.
+    ///
     /// The internal storage assumes the generator was valid for a provided `'static`,
     /// but requires the generator to be valid for all provided lifetimes.
     /// That is, the `Remit` provided to the generator cannot be moved out,
@@ -331,55 +791,328 @@ impl<T, P> Generator<T, P> {
     ) -> GeneratorIterator<'s, T, P>
         where
             // insures fn is not implemented only for 'static
-            G: RemitWithLifetime<T, ()>,
+            G: for<'a> AsyncFnOnce(Remit<'a, T>),
             // insures P is properly defined, even if it actually has a lifetime
-            G: FnOnce(Remit<'static, T>) -> P,
+            G: StaticCall<(Remit<'static, T>, ), Output=P>,
     {
-        // SOUND: Pin passthrough; only `future` is inner-pinned.
-        // `future` only ever gets replaced via Option::insert
-        let inner = unsafe { self.get_unchecked_mut() };
-        let value = inner.values.get();
-        let mode = Mode::Pinned {
-            value,
-            // This becomes 'static, and the trait-guard is where the real protection is
-            _lifetime: PhantomData,
-        };
-        let future = gen(Remit(mode));
-        let future = inner.future.insert(future);
+        Generator::with_storage(self, gen)
+    }
+
+    #[allow(clippy::needless_lifetimes)]
+    /// The same as [`Generator::of()`] but allows passing a parameter in.
+    pub fn parameterized<'s, G, X>(
+        self: Pin<&'s mut Self>,
+        gen: G,
+        parameter: X,
+    ) -> GeneratorIterator<'s, T, P>
+        where
+            // insures fn is not implemented only for 'static
+            G: for<'a> AsyncFnOnce(X, Remit<'a, T>),
+            // insures P is properly defined, even if it actually has a lifetime
+            G: StaticCall<(X, Remit<'static, T>, ), Output=P>,
+    {
+        // SOUND: the trait-guard is where the lifetime protection is
+        unsafe { Generator::start_in(self, |remit| gen.call_static((parameter, remit, ))) }
+    }
+
+    #[allow(clippy::needless_lifetimes)]
+    /// The same as [`Generator::parameterized()`] but allows passing up to 8 parameters in,
+    /// provided as a tuple, before the [`Remit`].
+    ///
+    /// Each parameter is passed separately, such that each may borrow, including `&mut`.
+    /// Async closures need their parameters annotated, such as `remit: Remit<'_, T>`.
+    ///
+    /// ```
+    /// # use std::pin::pin;
+    /// # use remit::{Generator, Remit};
+    /// async fn split(text: &str, separator: char, seen: &mut usize, remit: Remit<'_, String>) {
+    ///     for word in text.split(separator) {
+    ///         *seen += 1;
+    ///         remit.value(word.to_uppercase()).await;
+    ///     }
+    /// }
+    /// let text = String::from("a,b,c");
+    /// let mut seen = 0;
+    /// assert_eq!(
+    ///     vec!["A", "B"],
+    ///     pin!(Generator::new())
+    ///         .parameterized_tuple(split, (&text, ',', &mut seen))
+    ///         .take(2)
+    ///         .collect::<Vec<_>>(),
+    /// );
+    /// assert_eq!(2, seen);
+    /// ```
+    pub fn parameterized_tuple<'s, G, X>(
+        self: Pin<&'s mut Self>,
+        gen: G,
+        parameters: X,
+    ) -> GeneratorIterator<'s, T, P>
+        where
+            // insures fn is not implemented only for 'static
+            G: RemitWithLifetime<T, X>,
+            X: Parameters<Remit<'static, T>>,
+            // insures P is properly defined, even if it actually has a lifetime
+            G: StaticCall<X::With, Output=P>,
+    {
+        // SOUND: the trait-guard is where the lifetime protection is
+        unsafe { Generator::start_in(self, |remit| gen.call_static(parameters.with(remit))) }
+    }
+
+    /// Provides an iterator to the callback, using pinned storage owned by the scope.
+    /// Stack based (does not use an allocation).
+    ///
+    /// The iterator's lifetime is unique to the callback, such that it can't escape the scope.
+    /// Otherwise, this is the same as [`Generator::of()`].
+    ///
+    /// ```
+    /// # use remit::{Generator, Remit};
+    /// let total: usize = Generator::scope(async |remit: Remit<'_, usize>| {
+    ///     for i in 1..=4 {
+    ///         remit.value(i).await;
+    ///     }
+    /// }, |iter| iter.sum());
+    /// assert_eq!(10, total);
+    /// ```
+    pub fn scope<G, R>(
+        gen: G,
+        f: impl FnOnce(GeneratorIterator<'_, T, P>) -> R,
+    ) -> R
+        where
+            // insures fn is not implemented only for 'static
+            G: for<'a> AsyncFnOnce(Remit<'a, T>),
+            // insures P is properly defined, even if it actually has a lifetime
+            G: StaticCall<(Remit<'static, T>, ), Output=P>,
+    {
+        f(pin!(Generator::new()).of(gen))
+    }
+
+    /// The same as [`Generator::scope()`] but allows passing a parameter in.
+    ///
+    /// Otherwise, this is the same as [`Generator::parameterized()`].
+    pub fn scope_with<G, X, R>(
+        gen: G,
+        parameter: X,
+        f: impl FnOnce(GeneratorIterator<'_, T, P>) -> R,
+    ) -> R
+        where
+            // insures fn is not implemented only for 'static
+            G: for<'a> AsyncFnOnce(X, Remit<'a, T>),
+            // insures P is properly defined, even if it actually has a lifetime
+            G: StaticCall<(X, Remit<'static, T>, ), Output=P>,
+    {
+        f(pin!(Generator::new()).parameterized(gen, parameter))
+    }
+
+    /// The size of the storage, which is pinned in place when not using an allocation.
+    ///
+    /// Usable at compile time, to budget stack usage.
+    ///
+    /// ```
+    /// # use std::future::Ready;
+    /// # use remit::Generator;
+    /// const _: () = assert!(Generator::<u64, Ready<()>>::STORAGE_SIZE < 64);
+    /// ```
+    pub const STORAGE_SIZE: usize = mem::size_of::<Self>();
+
+    /// The same as [`Generator::scope()`] when the [storage](Generator::STORAGE_SIZE)
+    /// is at most [`AUTO_INLINE_LIMIT`], otherwise uses an allocation like [`Generator::boxed()`].
+    ///
+    /// Large futures are then kept off of a small stack.
+    /// When kept inline, the storage is only on the stack for the duration of the callback.
+    /// Without `alloc`, the storage is always inline.
+    ///
+    /// ```
+    /// # use remit::{Generator, Remit};
+    /// async fn small(remit: Remit<'_, usize>) {
+    ///     for i in 1..=4 {
+    ///         remit.value(i).await;
+    ///     }
+    /// }
+    /// async fn large(remit: Remit<'_, usize>) {
+    ///     let buffer = [1; 8192];
+    ///     for i in buffer.iter().step_by(2048) {
+    ///         remit.value(*i).await;
+    ///     }
+    /// }
+    /// assert_eq!(10, Generator::auto(small, |iter| iter.sum::<usize>()));
+    /// assert_eq!(4, Generator::auto(large, |iter| iter.sum::<usize>()));
+    /// ```
+    pub fn auto<G, R>(
+        gen: G,
+        f: impl FnOnce(GeneratorIterator<'_, T, P>) -> R,
+    ) -> R
+        where
+            // insures fn is not implemented only for 'static
+            G: for<'a> AsyncFnOnce(Remit<'a, T>),
+            // insures P is properly defined, even if it actually has a lifetime
+            G: StaticCall<(Remit<'static, T>, ), Output=P>,
+    {
+        Generator::auto_with_limit::<AUTO_INLINE_LIMIT, G, R>(gen, f)
+    }
+
+    /// The same as [`Generator::auto()`], but with the largest storage kept inline provided,
+    /// such as for a thread with a smaller or larger stack.
+    ///
+    /// ```
+    /// # use remit::{Generator, Remit};
+    /// async fn gen(remit: Remit<'_, usize>) {
+    ///     let buffer = [1; 512];
+    ///     for i in buffer.iter().step_by(128) {
+    ///         remit.value(*i).await;
+    ///     }
+    /// }
+    /// assert_eq!(4, Generator::auto_with_limit::<256, _, _>(gen, |iter| iter.sum::<usize>()));
+    /// ```
+    pub fn auto_with_limit<const LIMIT: usize, G, R>(
+        gen: G,
+        f: impl FnOnce(GeneratorIterator<'_, T, P>) -> R,
+    ) -> R
+        where
+            // insures fn is not implemented only for 'static
+            G: for<'a> AsyncFnOnce(Remit<'a, T>),
+            // insures P is properly defined, even if it actually has a lifetime
+            G: StaticCall<(Remit<'static, T>, ), Output=P>,
+    {
+        #[cfg(feature = "alloc")]
+        if Self::STORAGE_SIZE > LIMIT {
+            return f(Generator::boxed(|remit| gen.call_static((remit, ))))
+        }
+        Generator::scope_inline(gen, f)
+    }
+
+    #[inline(never)]
+    /// The same as [`Generator::scope()`], but kept in its own stack frame.
+    fn scope_inline<G, R>(
+        gen: G,
+        f: impl FnOnce(GeneratorIterator<'_, T, P>) -> R,
+    ) -> R
+        where
+            G: for<'a> AsyncFnOnce(Remit<'a, T>),
+            G: StaticCall<(Remit<'static, T>, ), Output=P>,
+    {
+        f(pin!(Generator::new()).of(gen))
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Uses an allocation so that the iterator does not need to be borrowed.
+    /// Useful for returning an iterator from a function, where it can't be pinned to the stack.
+    ///
+    /// The generator only needs to be valid for `'static`; it does not need to be valid for all lifetimes.
+    ///
+    /// To pass in parameters, use a capturing closure.
+    pub fn boxed(gen: impl FnOnce(Remit<'static, T>) -> P) -> GeneratorIterator<'static, T, P> {
+        let (owner, mode, future) = Cycler::allocate();
+        // SOUND: Writing to an UnsafeCell.
+        // Only spot where it's being written, having been freshly created.
+        //
+        // NEED: unsafe-cell lets shared-references to not conflict with exclusive-reference to future
+        unsafe { *future = Some(gen(Remit(mode))) };
+
         GeneratorIterator {
             done: false,
             mode,
             future,
-            #[cfg(feature = "alloc")]
-            _owner: None,
+            _owner: Some(owner),
+            _storage: None,
         }
     }
 
+    #[cfg(feature = "alloc")]
+    /// The same as [`Generator::boxed()`] but allows passing up to 8 parameters in,
+    /// provided as a tuple, before the [`Remit`].
+    ///
+    /// The parameters may borrow data that is not `'static`, with the iterator limited accordingly.
+    ///
+    /// ```
+    /// # use remit::{Generator, GeneratorIterator, Remit};
+    /// async fn numbered(lines: &[&str], prefix: &str, counter: &mut usize, remit: Remit<'static, String>) {
+    ///     for line in lines {
+    ///         *counter += 1;
+    ///         remit.value(format!("{prefix}{counter}: {line}")).await;
+    ///     }
+    /// }
+    /// fn iter<'a>(
+    ///     lines: &'a [&'a str],
+    ///     counter: &'a mut usize,
+    /// ) -> GeneratorIterator<'static, String, impl std::future::Future<Output=()> + 'a> {
+    ///     Generator::boxed_parameterized_tuple(numbered, (lines, "#", counter))
+    /// }
+    /// let mut counter = 10;
+    /// assert_eq!(
+    ///     vec!["#11: first", "#12: second"],
+    ///     iter(&["first", "second"], &mut counter).collect::<Vec<_>>(),
+    /// );
+    /// assert_eq!(12, counter);
+    /// ```
+    pub fn boxed_parameterized_tuple<G, X>(gen: G, parameters: X) -> GeneratorIterator<'static, T, P>
+        where
+            X: Parameters<Remit<'static, T>>,
+            G: StaticCall<X::With, Output=P>,
+    {
+        Generator::boxed(|remit| gen.call_static(parameters.with(remit)))
+    }
+
     #[allow(clippy::needless_lifetimes)]
-    /// The same as [`Generator::of()`] but allows passing a parameter in.
-    pub fn parameterized<'s, G, X>(
+    /// Takes the pinned storage and the generator and provides a consumer.
+    /// Stack based (does not use an allocation).
+    ///
+    /// The same lifetime relaxation as [`Generator::of()`] applies to the [`Receive`].
+    ///
+    /// ```
+    /// # use std::pin::pin;
+    /// # use remit::{Generator, Receive};
+    /// async fn sum(input: Receive<'_, usize>) -> usize {
+    ///     let mut sum = 0;
+    ///     while let Some(value) = input.next().await {
+    ///         sum += value;
+    ///     }
+    ///     sum
+    /// }
+    /// let mut storage = pin!(Generator::new());
+    /// let mut consumer = storage.as_mut().consumer(sum);
+    /// for value in [2, 3, 5, 7] {
+    ///     consumer.feed(value);
+    /// }
+    /// assert_eq!(Some(17), consumer.finish());
+    /// ```
+    ///
+    /// Once the consumer completes, the storage is also finished.
+    /// ```
+    /// # use std::pin::pin;
+    /// # use remit::{Generator, Receive};
+    /// async fn sink(input: Receive<'_, usize>) {
+    ///     while input.next().await.is_some() {}
+    /// }
+    /// let mut storage = pin!(Generator::new());
+    /// let mut consumer = storage.as_mut().consumer(sink);
+    /// consumer.feed(1);
+    /// assert_eq!(Some(()), consumer.finish());
+    /// assert_eq!(None, storage.as_mut().resume_iter().next());
+    /// ```
+    pub fn consumer<'s, G>(
         self: Pin<&'s mut Self>,
         gen: G,
-        parameter: X,
-    ) -> GeneratorIterator<'s, T, P>
+    ) -> Consumer<'s, T, P>
         where
+            P: Future,
             // insures fn is not implemented only for 'static
-            G: RemitWithLifetime<T, (X,)>,
+            G: for<'a> AsyncFnOnce(Receive<'a, T>) -> P::Output,
             // insures P is properly defined, even if it actually has a lifetime
-            G: FnOnce(X, Remit<'static, T>) -> P,
+            G: StaticCall<(Receive<'static, T>, ), Output=P>,
     {
         // SOUND: Pin passthrough; only `future` is inner-pinned.
-        // `future` only ever gets replaced via Option::insert
+        // `future` only ever gets replaced via Option::insert, or dropped in place
         let inner = unsafe { self.get_unchecked_mut() };
-        let value = inner.values.get();
+        inner.clear();
         let mode = Mode::Pinned {
-            value,
+            exchange: &inner.exchange,
             // This becomes 'static, and the trait-guard is where the real protection is
             _lifetime: PhantomData,
         };
-        let future = gen(parameter, Remit(mode));
+        let future = gen.call_static((Receive(mode), ));
         let future = inner.future.insert(future);
-        GeneratorIterator {
+        Consumer {
+            output: None,
             done: false,
             mode,
             future,
@@ -389,104 +1122,149 @@ impl<T, P> Generator<T, P> {
     }
 
     #[cfg(feature = "alloc")]
-    /// Uses an allocation so that the iterator does not need to be borrowed.
-    /// Useful for returning an iterator from a function, where it can't be pinned to the stack.
+    /// Uses an allocation so that the consumer does not need to be borrowed.
     ///
     /// The generator only needs to be valid for `'static`; it does not need to be valid for all lifetimes.
     ///
-    /// To pass in parameters, use a capturing closure.
-    pub fn boxed(gen: impl FnOnce(Remit<'static, T>) -> P) -> GeneratorIterator<'static, T, P> {
-        let rc = Rc::new(Cycler {
-            future: Default::default(),
-            references: References::new::<P>(),
-            weak_inner: UnsafeCell::new(MaybeUninit::uninit()),
-            _pin: Default::default(),
-        });
-        let weak = Rc::downgrade(&rc);
-        // SOUND: Writing to an UnsafeCell.
-        // Only spot where it's being written, having been freshly created.
-        //
-        // NEED: unsafe-cell lets shared-references to not conflict with exclusive-reference to weak_inner
-        let ptr = unsafe { &mut *rc.weak_inner.get() }.write(weak);
-        // SOUND: no re-use of ptr
-        // SOUND: !Send !Sync respected, via `*mut P` in GeneratorIterator.
-        rc.references.ptr.set(unsafe { Cycler::<P, T>::ptr_convert(ptr) });
-
-        let mode = Mode::Boxed(&rc.references);
+    /// ```
+    /// # use remit::{Consumer, Generator, Receive};
+    /// fn lines() -> Consumer<'static, char, impl std::future::Future<Output=Vec<String>>> {
+    ///     Generator::boxed_consumer(|input: Receive<'static, char>| async move {
+    ///         let mut lines = vec![String::new()];
+    ///         while let Some(c) = input.next().await {
+    ///             match c {
+    ///                 '\n' => lines.push(String::new()),
+    ///                 c => lines.last_mut().unwrap().push(c),
+    ///             }
+    ///         }
+    ///         lines
+    ///     })
+    /// }
+    /// let mut consumer = lines();
+    /// "Hello\nWorld".chars().for_each(|c| consumer.feed(c));
+    /// assert_eq!(Some(vec!["Hello".to_string(), "World".to_string()]), consumer.finish());
+    /// ```
+    pub fn boxed_consumer(gen: impl FnOnce(Receive<'static, T>) -> P) -> Consumer<'static, T, P>
+        where
+            P: Future,
+    {
+        let (owner, mode, future) = Cycler::allocate();
         // SOUND: Writing to an UnsafeCell.
         // Only spot where it's being written, having been freshly created.
         //
         // NEED: unsafe-cell lets shared-references to not conflict with exclusive-reference to future
-        let future = unsafe { &mut *rc.future.get() }.insert(gen(Remit(mode)));
+        let future = unsafe { &mut *future }.insert(gen(Receive(mode)));
 
-        GeneratorIterator {
+        Consumer {
+            output: None,
             done: false,
             mode,
             future,
-            _owner: Some(rc),
+            _owner: Some(owner),
         }
     }
 }
 
 #[cfg(feature = "alloc")]
+/// The part of a boxed allocation shared with the [`Remit`] or [`Receive`],
+/// with the type of the future erased.
 struct References<T> {
-    interchange: UnsafeCell<Values<T>>,
-    dropper: unsafe fn(*mut ()),
-    checker: unsafe fn(*mut ()) -> bool,
-    ptr: Cell<*mut ()>,
-}
-
-#[cfg(feature = "alloc")]
-impl<T> References<T> {
-    fn new<P>() -> Self {
-        References {
-            interchange: UnsafeCell::new(Values::Missing),
-            dropper: Cycler::<P, T>::do_inner_drop,
-            checker: Cycler::<P, T>::is_strong,
-            // Note that `null_mut` is only until the surrounding Rc gets created.
-            ptr: Cell::new(null_mut()),
-        }
-    }
+    interchange: Exchange<T>,
+    /// Whether the owning iterator, consumer, or transducer has not been dropped.
+    alive: Cell<bool>,
+    /// The owner, and the `Remit` or `Receive` unless dropped.
+    count: Cell<usize>,
+    /// Drops the future in place.
+    close: unsafe fn(*const References<T>),
+    /// Frees the allocation.
+    free: unsafe fn(*const References<T>),
+    #[cfg(feature = "allocator-api2")]
+    /// The custom allocator of the allocation, which also allocates spilled values.
+    alloc: Option<allocator::Erased>,
 }
 
 #[cfg(feature = "alloc")]
+#[repr(C)]
+/// A single allocation, holding the future along with the values in transit.
+// NEED: `references` is first, such that a ptr to it is a ptr to the Cycler
 struct Cycler<P, T> {
-    future: UnsafeCell<Option<P>>,
     references: References<T>,
-    weak_inner: UnsafeCell<MaybeUninit<Weak<Cycler<P, T>>>>,
+    future: UnsafeCell<Option<P>>,
     _pin: PhantomPinned,
 }
 
 #[cfg(feature = "alloc")]
 impl<P, T> Cycler<P, T> {
-    #[inline(always)]
-    /// Exclusive-ref must not reused.
-    /// Resulting ptr must be kept !Send !Sync
+    /// Creates the allocation, shared by the owner, and the single `Remit` or `Receive` created from the mode.
+    /// Also provides the slot for the future.
+    fn allocate() -> (Owner<T>, Mode<'static, T>, *mut Option<P>) {
+        let ptr = Box::into_raw(Box::new(Cycler {
+            references: References {
+                interchange: Exchange::new(),
+                alive: Cell::new(true),
+                count: Cell::new(2),
+                close: Cycler::<P, T>::close,
+                free: Cycler::<P, T>::free,
+                #[cfg(feature = "allocator-api2")]
+                alloc: None,
+            },
+            future: UnsafeCell::new(None),
+            _pin: PhantomPinned,
+        }));
+        // SOUND: freshly allocated, and not yet shared
+        let references = unsafe { addr_of!((*ptr).references) };
+        // SOUND: see above
+        let future = unsafe { (*ptr).future.get() };
+        (Owner(references), Mode::Boxed(references), future)
+    }
+
+    /// ptr must be from this Cycler's allocate, and not freed.
     // NEED: erasing Cycler's storage generic, which ends up recursive
-    unsafe fn ptr_convert(ptr: &mut Weak<Cycler<P, T>>) -> *mut () {
-        ptr as _
+    unsafe fn close(ptr: *const References<T>) {
+        let ptr: *const Cycler<P, T> = ptr as _;
+        // SOUND: (valid-ptr) repr(C) with references first
+        // SOUND: (&mut exclusive) only the owner touches the future, which is closing
+        *(*ptr).future.get() = None;
     }
 
-    /// ptr must be created with this Cycler's ptr_convert.
-    /// May only be called once.
+    /// ptr must be from this Cycler's allocate, and may only be called once.
     // NEED: erasing Cycler's storage generic, which ends up recursive
-    unsafe fn do_inner_drop(ptr: *mut ()) {
-        let ptr: *mut Weak<Cycler<P, T>> = ptr as _;
-        // SOUND: (Rc-race-condition) ptr_convert requires !Send !Sync
-        // SOUND: (valid-ptr) ptr_convert instantiation
+    unsafe fn free(ptr: *const References<T>) {
+        let ptr: *mut Cycler<P, T> = ptr as _;
+        // SOUND: (valid-ptr) repr(C) with references first, from Box::into_raw
         // SOUND: (double-drop) can only be called once
-        let _: Weak<Cycler<P, T>> = read(ptr);
+        drop(Box::from_raw(ptr));
     }
+}
 
-    /// ptr must be created with this Cycler's ptr_convert.
-    /// Must not be called after do_inner_drop.
-    // NEED: erasing Cycler's storage generic, which ends up recursive
-    unsafe fn is_strong(ptr: *mut ()) -> bool {
-        let ptr: *const Weak<Cycler<P, T>> = ptr as _;
-        // SOUND: (use-after-free) can't be called after do_inner_drop
-        // SOUND: (valid-ptr) ptr_convert instantiation
-        // SOUND: (no exclusive ref violation) only exclusive-ref is do_inner_drop
-        (*ptr).strong_count() > 0
+#[cfg(feature = "alloc")]
+/// Owns a boxed allocation, dropping the future when dropped.
+///
+/// The allocation is freed once the `Remit` or `Receive` is also dropped.
+struct Owner<T>(*const References<T>);
+
+#[cfg(feature = "alloc")]
+impl<T> Drop for Owner<T> {
+    fn drop(&mut self) {
+        let ptr = self.0;
+        // SOUND: (valid-ptr) Not-pub, valid at instantiation, and only freed here or by dropping()
+        //
+        // SOUND: (use-after-free) count includes the owner until the end
+        //
+        // SOUND: (no exclusive ref violation)
+        // * `*const ptr`s never borrowed exclusively
+        // * ptrs never leaked
+        // * !Send, !Sync
+        unsafe {
+            // Remit or Receive sees this while the future drops
+            (*ptr).alive.set(false);
+            ((*ptr).close)(ptr);
+            let count = (*ptr).count.get() - 1;
+            (*ptr).count.set(count);
+            if count == 0 {
+                ((*ptr).free)(ptr);
+            }
+        }
     }
 }
 
@@ -497,12 +1275,100 @@ impl<P, T> Cycler<P, T> {
 /// If one or more values are available, it will not poll until they have been consumed.
 ///
 /// The upper-bound of `size_hint` will be `None` iff the future has not completed.
+///
+/// When the iterator of pinned storage is dropped, the future is dropped in place,
+/// along with any values that were not consumed, as is done for a boxed iterator.
+/// To instead keep them in the storage, use [`GeneratorIterator::detach()`].
+///
+/// ```
+/// # use std::pin::pin;
+/// # use remit::{Generator, Remit};
+/// # use std::cell::Cell;
+/// struct Guard<'a>(&'a Remit<'a, usize>, &'a Cell<&'static str>);
+///
+/// impl Drop for Guard<'_> {
+///     fn drop(&mut self) {
+///         self.1.set(if self.0.is_closed() { "cancelled" } else { "finished" });
+///     }
+/// }
+///
+/// let state = Cell::new("running");
+/// let mut storage = pin!(Generator::new());
+/// let mut iter = storage.as_mut().of(async |remit| {
+///     let _guard = Guard(&remit, &state);
+///     for i in 1.. {
+///         remit.value(i).await;
+///     }
+/// });
+/// assert_eq!(Some(1), iter.next());
+/// drop(iter);
+/// assert!(!storage.is_running());
+/// assert_eq!("cancelled", state.get());
+/// ```
 pub struct GeneratorIterator<'a, T, P> {
     done: bool,
     mode: Mode<'a, T>,
-    future: *mut P,
+    future: *mut Option<P>,
     #[cfg(feature = "alloc")]
-    _owner: Option<Rc<Cycler<P, T>>>,
+    _owner: Option<Owner<T>>,
+    _storage: Option<Storage>,
+}
+
+impl<T, P> GeneratorIterator<'_, T, P> {
+    /// Ends the iterator's borrow of the pinned storage,
+    /// keeping the future and any values that were not consumed in the storage.
+    ///
+    /// Use [`Generator::resume_iter()`] to continue iterating.
+    /// When boxed, or the storage is owned by the iterator,
+    /// the future and values are dropped along with the storage.
+    ///
+    /// Otherwise, the future is dropped along with the storage, before any values,
+    /// such that it may still remit while dropping.
+    /// ```
+    /// # use std::pin::pin;
+    /// # use remit::{Generator, Remit};
+    /// # use std::cell::Cell;
+    /// struct Guard<'a>(&'a Remit<'a, usize>, &'a Cell<bool>);
+    ///
+    /// impl Drop for Guard<'_> {
+    ///     fn drop(&mut self) {
+    ///         let _ = self.0.value(0);
+    ///         self.1.set(true);
+    ///     }
+    /// }
+    ///
+    /// let dropped = Cell::new(false);
+    /// {
+    ///     let mut storage = pin!(Generator::new());
+    ///     let mut iter = storage.as_mut().of(async |remit| {
+    ///         let _guard = Guard(&remit, &dropped);
+    ///         for i in 1.. {
+    ///             remit.value(i).await;
+    ///         }
+    ///     });
+    ///     assert_eq!(Some(1), iter.next());
+    ///     iter.detach();
+    /// }
+    /// assert!(dropped.get());
+    /// ```
+    pub fn detach(self) {
+        if self.mode.is_pinned() && self._storage.is_none() {
+            // The storage is left as-is, for a later iterator to resume from.
+            // Nothing is owned by the iterator itself.
+            mem::forget(self);
+        }
+    }
+}
+
+impl<T, P> Drop for GeneratorIterator<'_, T, P> {
+    fn drop(&mut self) {
+        if self.mode.is_pinned() {
+            self.mode.close();
+            // SOUND: see GeneratorIterator::next(), with the future dropped in place
+            unsafe { *self.future = None };
+            self.mode.clear();
+        }
+    }
 }
 
 impl<T, P: Future<Output=()>> Iterator for GeneratorIterator<'_, T, P> {
@@ -514,10 +1380,6 @@ impl<T, P: Future<Output=()>> Iterator for GeneratorIterator<'_, T, P> {
         if self.done {
             return None
         }
-        // FIXME: https://github.com/rust-lang/rust/issues/102012
-        // SOUND: We can't use Arc without alloc,
-        // so context just defines some no-operation functions to fill out a v-table.
-        let waker = unsafe { Waker::from_raw(context::NOOP_WAKER) };
         // SOUND: (pinning) Sound, we created the ptr to future ourselves and it was pinned,
         // either via Rc or via a pinned-self.
         //
@@ -530,8 +1392,9 @@ impl<T, P: Future<Output=()>> Iterator for GeneratorIterator<'_, T, P> {
         // either owned in _owner, or pinned-self.
         //
         // SOUND: (valid-ptr) Not-pub, and is always valid at instantiation.
-        if let Poll::Ready(()) = unsafe { Pin::new_unchecked(&mut *self.future) }.poll(&mut Context::from_waker(&waker)) {
+        if let Poll::Ready(()) = unsafe { poll_slot(self.future) } {
             self.done = true;
+            self.mode.finish();
         }
         self.mode.next()
     }
@@ -546,16 +1409,122 @@ impl<T, P: Future<Output=()>> Iterator for GeneratorIterator<'_, T, P> {
     }
 }
 
+/// A driver for a generator that receives values instead of remitting them.
+///
+/// Values are pushed in with [`Consumer::feed()`], each of which polls the generator
+/// so that it may [`Receive::next()`] them.
+/// Calling [`Consumer::finish()`] signals that no more values will be provided,
+/// and polls the generator a final time to retrieve its output.
+///
+/// If the generator finishes early, any values subsequently fed are discarded.
+pub struct Consumer<'a, T, P: Future> {
+    output: Option<P::Output>,
+    done: bool,
+    mode: Mode<'a, T>,
+    future: *mut P,
+    #[cfg(feature = "alloc")]
+    _owner: Option<Owner<T>>,
+}
+
+impl<T, P: Future> Consumer<'_, T, P> {
+    /// Provides a value to the generator, and polls it.
+    ///
+    /// If the generator does not receive the value before awaiting on something else,
+    /// the value is buffered with `alloc`.
+    /// Otherwise, like [`Remit::value()`], the behavior is SemVer
+    /// [unspecified](https://doc.rust-lang.org/reference/behavior-not-considered-unsafe.html).
+    pub fn feed(&mut self, value: T) {
+        if self.done {
+            return
+        }
+        self.mode.push(value);
+        self.poll();
+    }
+
+    /// Indicates that the generator has finished, and will not receive any more values.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Signals the end of input to the generator, and provides its output.
+    ///
+    /// Returns `None` iff the generator awaits anything other than [`Receive::next()`]
+    /// after having received the end of input.
+    pub fn finish(mut self) -> Option<P::Output> {
+        self.mode.close();
+        self.poll();
+        self.output.take()
+    }
+
+    fn poll(&mut self) {
+        if self.done {
+            return
+        }
+        // SOUND: see GeneratorIterator::next()
+        if let Poll::Ready(output) = unsafe { poll(self.future) } {
+            self.output = Some(output);
+            self.done = true;
+            self.mode.finish();
+        }
+    }
+}
+
+/// Polls the future in the slot once, being ready if there is no future.
+///
+/// The ptr must be valid, pinned, and not otherwise borrowed.
+unsafe fn poll_slot<P: Future<Output=()>>(slot: *mut Option<P>) -> Poll<()> {
+    match &mut *slot {
+        Some(future) => poll(future),
+        None => Poll::Ready(()),
+    }
+}
+
+/// Polls the future once, using a no-operation waker.
+///
+/// The ptr must be valid, pinned, and not otherwise borrowed.
+unsafe fn poll<P: Future + ?Sized>(future: *mut P) -> Poll<P::Output> {
+    // FIXME: https://github.com/rust-lang/rust/issues/102012
+    // SOUND: We can't use Arc without alloc,
+    // so context just defines some no-operation functions to fill out a v-table.
+    let waker = Waker::from_raw(context::NOOP_WAKER);
+    // SOUND: unsafe-fn, see above.
+    Pin::new_unchecked(&mut *future).poll(&mut Context::from_waker(&waker))
+}
+
+/// The values in transit, along with whether any more values will be sent,
+/// and whether the future was seen to have finished.
+struct Exchange<T> {
+    values: UnsafeCell<Values<T>>,
+    closed: Cell<bool>,
+    finished: Cell<bool>,
+}
+
+impl<T> Exchange<T> {
+    const fn new() -> Self {
+        Exchange {
+            values: UnsafeCell::new(Values::Missing),
+            closed: Cell::new(false),
+            finished: Cell::new(false),
+        }
+    }
+}
+
 enum Values<T> {
     Present(T),
     Missing,
     #[cfg(feature = "alloc")]
+    /// Kept inline, before spilling to the heap.
+    Pair(T, T),
+    #[cfg(feature = "alloc")]
     Multiple(VecDeque<T>),
+    #[cfg(feature = "allocator-api2")]
+    /// Spilled into the custom allocator of a boxed allocation.
+    Spilled(allocator::Spill<T>),
 }
 
 enum Mode<'a, T> {
     Pinned {
-        value: *mut Values<T>,
+        exchange: *const Exchange<T>,
         _lifetime: PhantomData<&'a ()>,
     },
     #[cfg(feature = "alloc")]
@@ -566,22 +1535,27 @@ impl<T> Clone for Mode<'_, T> {
     fn clone(&self) -> Self {
         *self
     }
-
-    fn clone_from(&mut self, source: &Self) {
-        *self = *source
-    }
 }
 
 impl<T> Copy for Mode<'_, T> {}
 
 impl<T> Mode<'_, T> {
     #[inline(always)]
-    fn values(&self) -> *mut Values<T> {
+    fn is_pinned(&self) -> bool {
+        match self {
+            Mode::Pinned { .. } => true,
+            #[cfg(feature = "alloc")]
+            Mode::Boxed(_) => false,
+        }
+    }
+
+    #[inline(always)]
+    fn exchange(&self) -> *const Exchange<T> {
         match *self {
             Mode::Pinned {
-                value,
+                exchange,
                 ..
-            } => value,
+            } => exchange,
             #[cfg(feature = "alloc")]
             // SOUND: (valid-ptr) Not-pub, and is always valid at instantiation.
             //
@@ -592,10 +1566,45 @@ impl<T> Mode<'_, T> {
             // * ptr never leaked
             //
             // NEED: erasing Cycler's storage generic, which ends up recursive
-            Mode::Boxed(ptr) => unsafe { &*addr_of!((*ptr).interchange) }.get()
+            Mode::Boxed(ptr) => unsafe { addr_of!((*ptr).interchange) },
         }
     }
 
+    #[inline(always)]
+    fn values(&self) -> *mut Values<T> {
+        // SOUND: (valid-ptr) Not-pub, and is always valid at instantiation.
+        //
+        // SOUND: (use-after-free) Not public type.
+        // Either encapsulating type owns it, or reflected in lifetime.
+        //
+        // SOUND: (no exclusive ref violation) `Exchange` is never borrowed exclusively
+        unsafe { &*self.exchange() }.values.get()
+    }
+
+    #[inline(always)]
+    fn close(&self) {
+        // SOUND: see values()
+        unsafe { &*self.exchange() }.closed.set(true)
+    }
+
+    #[inline(always)]
+    fn is_closed(&self) -> bool {
+        // SOUND: see values()
+        unsafe { &*self.exchange() }.closed.get()
+    }
+
+    #[inline(always)]
+    fn clear(&self) {
+        // SOUND: see next(); the values are dropped after the exclusive-reference is gone
+        let _: Values<T> = mem::replace(unsafe { &mut *self.values() }, Values::Missing);
+    }
+
+    #[inline(always)]
+    fn finish(&self) {
+        // SOUND: see values()
+        unsafe { &*self.exchange() }.finished.set(true)
+    }
+
     #[inline(always)]
     fn next(&self) -> Option<T> {
         // SOUND: (valid-ptr) Not-pub, and is always valid at instantiation.
@@ -626,7 +1635,18 @@ impl<T> Mode<'_, T> {
                     unsafe { unreachable_unchecked() }
                 },
             #[cfg(feature = "alloc")]
+            Pair(..) =>
+                if let Pair(first, second) = mem::replace(values, Missing) {
+                    *values = Present(second);
+                    Some(first)
+                } else {
+                    // SOUND: note exclusive-reference and surrounding match
+                    unsafe { unreachable_unchecked() }
+                },
+            #[cfg(feature = "alloc")]
             Multiple(list) => list.pop_front(),
+            #[cfg(feature = "allocator-api2")]
+            Spilled(list) => list.pop_front(),
         }
     }
 
@@ -645,11 +1665,12 @@ impl<T> Mode<'_, T> {
         //
         // NEED: lock-free exchange
         // NEED: pinned-variant's lifetime cheat
-        let _ = Self::push_inner(unsafe { &mut *self.values() }, value);
+        #[allow(clippy::let_unit_value)]
+        let _ = self.push_inner(unsafe { &mut *self.values() }, value);
     }
 
     #[cfg(feature = "alloc")]
-    fn push_inner(values: &mut Values<T>, value: T) {
+    fn push_inner(&self, values: &mut Values<T>, value: T) {
         use Values::*;
         match values {
             Missing => *values = Present(value),
@@ -659,17 +1680,43 @@ impl<T> Mode<'_, T> {
                         // SOUND: note exclusive-reference and surrounding match
                         unsafe { unreachable_unchecked() }
                     };
-                let mut list = VecDeque::with_capacity(2);
-                list.push_back(old);
+                *values = Pair(old, value);
+            },
+            Pair(..) => {
+                let Pair(first, second) = mem::replace(values, Missing)
+                    else {
+                        // SOUND: note exclusive-reference and surrounding match
+                        unsafe { unreachable_unchecked() }
+                    };
+                #[cfg(feature = "allocator-api2")]
+                if let Some(alloc) = self.allocator() {
+                    *values = Spilled(allocator::Spill::new(alloc, [first, second, value]));
+                    return
+                }
+                let mut list = VecDeque::with_capacity(4);
+                list.push_back(first);
+                list.push_back(second);
                 list.push_back(value);
                 *values = Multiple(list);
             },
             Multiple(list) => list.push_back(value),
+            #[cfg(feature = "allocator-api2")]
+            Spilled(list) => list.push_back(value),
+        }
+    }
+
+    #[cfg(feature = "allocator-api2")]
+    /// The custom allocator of a boxed allocation, if any.
+    fn allocator(&self) -> Option<allocator::Erased> {
+        match *self {
+            // SOUND: see exchange()
+            Mode::Boxed(ptr) => unsafe { (*ptr).alloc },
+            Mode::Pinned { .. } => None,
         }
     }
 
     #[cfg(not(feature = "alloc"))]
-    fn push_inner(values: &mut Values<T>, value: T) -> Values<T> {
+    fn push_inner(&self, values: &mut Values<T>, value: T) -> Values<T> {
         mem::replace(values, Values::Present(value))
     }
 
@@ -696,7 +1743,11 @@ impl<T> Mode<'_, T> {
             Present(_) => 1,
             Missing => 0,
             #[cfg(feature = "alloc")]
+            Pair(..) => 2,
+            #[cfg(feature = "alloc")]
             Multiple(list) => list.len(),
+            #[cfg(feature = "allocator-api2")]
+            Spilled(list) => list.len(),
         }
     }
 
@@ -717,13 +1768,62 @@ impl<T> Mode<'_, T> {
         Self::is_empty_inner(unsafe { &*self.values() })
     }
 
+    #[cfg(feature = "alloc")]
+    /// Requires the box-ptr to be instantiated correctly.
+    /// May not be called after dropping.
+    // SOUND: (use-after-free) cannot be called after dropping()
+    //
+    // SOUND: (no exclusive ref violation)
+    // * `*const ptr`s never borrowed exclusively
+    // * ptrs never leaked
+    //
+    // NEED: erasing Cycler's storage generic, which ends up recursive
+    // NEED: use-after-free prevention of value-exchange
+    unsafe fn strong(&self) -> bool {
+        if let Mode::Boxed(ptr) = *self {
+            // SOUND: not freed while the Remit or Receive remains
+            (*ptr).alive.get()
+        } else {
+            true
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Requires the box-ptr to be instantiated correctly,
+    /// and may only be called once.
+    //
+    // SOUND: (use-after-free) free occurs here, and not read after
+    //
+    // SOUND: (no exclusive ref violation)
+    // * `*const ptr`s never borrowed exclusively
+    // * ptrs never leaked
+    //
+    // NEED: erasing Cycler's storage generic, which ends up recursive
+    unsafe fn dropping(&mut self) {
+        if let Mode::Boxed(ptr) = *self {
+            let count = (*ptr).count.get() - 1;
+            (*ptr).count.set(count);
+            if count == 0 {
+                // SOUND: free is not pub, and only called once,
+                // as the owner already decremented the count
+                //
+                // SOUND: unsafe-fn, see Cycler::free
+                ((*ptr).free)(ptr)
+            }
+        }
+    }
+
     fn is_empty_inner(values: &Values<T>) -> bool {
         use Values::*;
         match values {
             Present(_) => false,
             Missing => true,
             #[cfg(feature = "alloc")]
+            Pair(..) => false,
+            #[cfg(feature = "alloc")]
             Multiple(list) => list.is_empty(),
+            #[cfg(feature = "allocator-api2")]
+            Spilled(list) => list.is_empty(),
         }
     }
 }
@@ -754,6 +1854,22 @@ impl<T> Remit<'_, T> {
         self.value_impl(value)
     }
 
+    /// Indicates that the iterator has been dropped, such that values are discarded.
+    ///
+    /// A generator only observes this while being dropped itself, such as from a guard,
+    /// or when a boxed generator moved the `Remit` elsewhere.
+    pub fn is_closed(&self) -> bool {
+        #[cfg(feature = "alloc")]
+        // SOUND: non-public field, valid at instantiation
+        // SOUND: self not dropped
+        //
+        // NEED: use-after-free prevention of value-exchange
+        if !unsafe { self.0.strong() } {
+            return true
+        }
+        self.0.is_closed()
+    }
+
     #[cfg(not(feature = "alloc"))]
     fn value_impl(&self, value: T) -> impl Future<Output=()> + '_ {
         self.0.push(value);
@@ -772,7 +1888,7 @@ impl<T> Remit<'_, T> {
         // SOUND: self not dropped
         //
         // NEED: use-after-free prevention of value-exchange
-        if unsafe { self.strong() } {
+        if unsafe { self.0.strong() } {
             self.0.push(value);
         }
         poll_fn(|_ctx|
@@ -780,70 +1896,65 @@ impl<T> Remit<'_, T> {
             // SOUND: self not dropped
             //
             // NEED: use-after-free prevention of value-exchange
-            if unsafe { self.strong() } && self.0.is_empty() {
+            if unsafe { self.0.strong() } && self.0.is_empty() {
                 Poll::Ready(())
             } else {
                 Poll::Pending
             }
         )
     }
+}
 
-    #[cfg(feature = "alloc")]
-    /// Requires the box-ptr to be instantiated correctly.
-    /// May not be called after dropping.
-    // SOUND: (use-after-free) cannot be called after dropping()
-    //
-    // SOUND: (no exclusive ref violation)
-    // * `*const ptr`s never borrowed exclusively
-    // * ptrs never leaked
-    //
-    // NEED: erasing Cycler's storage generic, which ends up recursive
-    // NEED: use-after-free prevention of value-exchange
-    unsafe fn strong(&self) -> bool {
-        if let &Remit(Mode::Boxed(ptr)) = self {
-            let inner_ptr = (*addr_of!((*ptr).ptr)).get();
-            // SOUND: checker is not pub, nor was inner_ptr,
-            // thus still valid from instantiation
-            //
-            // SOUND: unsafe-fn, see Cycler::is_strong
-            (*addr_of!((*ptr).checker))(inner_ptr)
-        } else {
-            true
-        }
+#[cfg(feature = "alloc")]
+impl<T> Drop for Remit<'_, T> {
+    fn drop(&mut self) {
+        // SOUND: Valid at instantiation
+        // SOUND: Only call-site of dropping for a Remit, and inner ptrs
+        unsafe { self.0.dropping() }
     }
+}
 
-    #[cfg(feature = "alloc")]
-    /// Requires the box-ptr to be instantiated correctly,
-    /// and may only be called once.
+/// Allows a generator to receive values from a [`Consumer`].
+/// A generator that only accepts the `'static` lifetime can only be used when boxed.
+pub struct Receive<'a, T>(Mode<'a, T>);
 
-    // SOUND: (use-after-free) free occurs here, and not read after
-    //
-    // SOUND: (no exclusive ref violation)
-    // * `*const ptr`s never borrowed exclusively
-    // * ptrs never leaked
-    //
-    // NEED: erasing Cycler's storage generic, which ends up recursive
-    unsafe fn dropping(&mut self) {
-        if let &mut Remit(Mode::Boxed(ptr)) = self {
-            let inner_ptr = (*addr_of!((*ptr).ptr)).get();
-            // SOUND: dropper is not pub, nor was inner_ptr,
-            // thus still valid from instantiation
-            //
-            // SOUND: dropper only called once for inner_ptr,
-            // as inner_ptr only exists in this struct,
-            // and dropping is only called once.
+impl<T> Receive<'_, T> {
+    /// Receives the next value provided to the consumer.
+    ///
+    /// The provided future finishes with `None` once the consumer has been finished,
+    /// and all values were received.
+    ///
+    /// The provided future does not awake on the consumer being fed values;
+    /// the consumer will poll the originally created future unilaterally.
+    ///
+    /// If the consumer has been dropped,
+    /// the future(s) will always poll as pending.
+    pub fn next(&self) -> impl Future<Output=Option<T>> + '_ {
+        poll_fn(|_ctx| {
+            #[cfg(feature = "alloc")]
+            // SOUND: non-public field, valid at instantiation
+            // SOUND: self not dropped
             //
-            // SOUND: unsafe-fn, see Cycler::do_inner_drop
-            (*addr_of!((*ptr).dropper))(inner_ptr)
-        }
+            // NEED: use-after-free prevention of value-exchange
+            if !unsafe { self.0.strong() } {
+                return Poll::Pending
+            }
+            if let Some(value) = self.0.next() {
+                Poll::Ready(Some(value))
+            } else if self.0.is_closed() {
+                Poll::Ready(None)
+            } else {
+                Poll::Pending
+            }
+        })
     }
 }
 
 #[cfg(feature = "alloc")]
-impl<T> Drop for Remit<'_, T> {
+impl<T> Drop for Receive<'_, T> {
     fn drop(&mut self) {
         // SOUND: Valid at instantiation
-        // SOUND: Only call-site of dropping, and inner ptrs
-        unsafe { self.dropping() }
+        // SOUND: Only call-site of dropping for a Receive, and inner ptrs
+        unsafe { self.0.dropping() }
     }
 }
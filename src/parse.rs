@@ -0,0 +1,360 @@
+//! Incremental parsing of input provided in chunks.
+//!
+//! An [`Input`] wraps the [`Receive`] of a transducer or consumer,
+//! buffering the received chunks until enough are available for each primitive.
+//! The primitives only await more chunks when the buffered input is insufficient,
+//! and finish with `None` when the input ends before they can be satisfied.
+//!
+//! ```
+//! # use remit::{Generator, Receive, Remit, Transduce};
+//! # use remit::parse::Input;
+//! #[derive(Debug, PartialEq)]
+//! enum Token {
+//!     Header(Vec<u8>, Vec<u8>),
+//!     Body(Vec<u8>),
+//! }
+//!
+//! async fn message(mut input: Input<'_, &'static [u8]>, remit: Remit<'_, Token>) -> Option<()> {
+//!     while input.peek().await? != b'\n' {
+//!         let name = input.take_until(b':').await?;
+//!         input.expect(b" ").await?;
+//!         let value = input.take_until(b'\n').await?;
+//!         remit.value(Token::Header(name, value)).await;
+//!     }
+//!     input.expect(b"\n").await?;
+//!     remit.value(Token::Body(input.rest().await)).await;
+//!     Some(())
+//! }
+//!
+//! let chunks: [&'static [u8]; 4] = [b"Length: 5\nTy", b"pe: text\n", b"\nhel", b"lo"];
+//! let tokens = chunks
+//!     .into_iter()
+//!     .transduce(|input, remit| async move { message(Input::new(input), remit).await; })
+//!     .collect::<Vec<_>>();
+//! assert_eq!(
+//!     vec![
+//!         Token::Header(b"Length".to_vec(), b"5".to_vec()),
+//!         Token::Header(b"Type".to_vec(), b"text".to_vec()),
+//!         Token::Body(b"hello".to_vec()),
+//!     ],
+//!     tokens,
+//! );
+//! ```
+
+use core::mem;
+
+use alloc::{
+    string::String,
+    vec::Vec,
+};
+
+use crate::Receive;
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for alloc::vec::Vec<u8> {}
+    impl Sealed for alloc::string::String {}
+}
+
+/// The storage of buffered input, being either bytes with `Vec<u8>`, or characters with `String`.
+///
+/// This trait is sealed, and its methods are not considered part of SemVer.
+pub trait Buffer: Default + sealed::Sealed {
+    /// The borrowed form of a chunk, or a tag.
+    type Slice: ?Sized;
+    /// A single unit of input.
+    type Item: Copy + PartialEq;
+
+    #[doc(hidden)]
+    fn append(&mut self, chunk: &Self::Slice);
+    /// The items at or after `from`.
+    #[doc(hidden)]
+    fn slice_from(&self, from: usize) -> &Self::Slice;
+    #[doc(hidden)]
+    fn slice_len(slice: &Self::Slice) -> usize;
+    #[doc(hidden)]
+    fn slice_starts_with(slice: &Self::Slice, prefix: &Self::Slice) -> bool;
+    /// The item at `from`.
+    #[doc(hidden)]
+    fn first(&self, from: usize) -> Option<Self::Item>;
+    /// The index after `count` items from `from`, if there are at least that many.
+    #[doc(hidden)]
+    fn boundary(&self, from: usize, count: usize) -> Option<usize>;
+    /// The index of the item at or after `from`, and the index after it.
+    #[doc(hidden)]
+    fn find(&self, item: Self::Item, from: usize) -> Option<(usize, usize)>;
+    /// A copy of the items from `from` until `to`.
+    #[doc(hidden)]
+    fn copy_range(&self, from: usize, to: usize) -> Self;
+    /// Removes the items before `at`.
+    #[doc(hidden)]
+    fn remove_front(&mut self, at: usize);
+}
+
+impl Buffer for Vec<u8> {
+    type Slice = [u8];
+    type Item = u8;
+
+    fn append(&mut self, chunk: &[u8]) {
+        self.extend_from_slice(chunk)
+    }
+
+    fn slice_from(&self, from: usize) -> &[u8] {
+        &self[from..]
+    }
+
+    fn slice_len(slice: &[u8]) -> usize {
+        slice.len()
+    }
+
+    fn slice_starts_with(slice: &[u8], prefix: &[u8]) -> bool {
+        slice.starts_with(prefix)
+    }
+
+    fn first(&self, from: usize) -> Option<u8> {
+        self.get(from).copied()
+    }
+
+    fn boundary(&self, from: usize, count: usize) -> Option<usize> {
+        from.checked_add(count).filter(|&end| end <= self.len())
+    }
+
+    fn find(&self, item: u8, from: usize) -> Option<(usize, usize)> {
+        self[from..]
+            .iter()
+            .position(|&byte| byte == item)
+            .map(|index| (from + index, from + index + 1))
+    }
+
+    fn copy_range(&self, from: usize, to: usize) -> Self {
+        self[from..to].to_vec()
+    }
+
+    fn remove_front(&mut self, at: usize) {
+        self.drain(..at);
+    }
+}
+
+impl Buffer for String {
+    type Slice = str;
+    type Item = char;
+
+    fn append(&mut self, chunk: &str) {
+        self.push_str(chunk)
+    }
+
+    fn slice_from(&self, from: usize) -> &str {
+        &self[from..]
+    }
+
+    fn slice_len(slice: &str) -> usize {
+        slice.len()
+    }
+
+    fn slice_starts_with(slice: &str, prefix: &str) -> bool {
+        slice.starts_with(prefix)
+    }
+
+    fn first(&self, from: usize) -> Option<char> {
+        self[from..].chars().next()
+    }
+
+    fn boundary(&self, from: usize, count: usize) -> Option<usize> {
+        self[from..]
+            .char_indices()
+            .map(|(index, _)| from + index)
+            .chain([self.len()])
+            .nth(count)
+    }
+
+    fn find(&self, item: char, from: usize) -> Option<(usize, usize)> {
+        self[from..]
+            .find(item)
+            .map(|index| (from + index, from + index + item.len_utf8()))
+    }
+
+    fn copy_range(&self, from: usize, to: usize) -> Self {
+        String::from(&self[from..to])
+    }
+
+    fn remove_front(&mut self, at: usize) {
+        self.drain(..at);
+    }
+}
+
+/// Buffered input received in chunks, for use by a parser.
+///
+/// Bytes are parsed with the default `Vec<u8>` buffer,
+/// and characters are parsed with a `String` buffer.
+///
+/// ```
+/// # use remit::{Generator, Receive};
+/// # use remit::parse::Input;
+/// async fn words(input: Receive<'static, String>) -> Vec<String> {
+///     let mut input = Input::<_, String>::new(input);
+///     let mut words = Vec::new();
+///     while let Some(word) = input.take_until(' ').await {
+///         words.push(word);
+///     }
+///     words.push(input.rest().await);
+///     words
+/// }
+/// let mut consumer = Generator::boxed_consumer(words);
+/// consumer.feed("Löwe 老".to_string());
+/// consumer.feed("虎 Léopard".to_string());
+/// assert_eq!(Some(vec!["Löwe".to_string(), "老虎".to_string(), "Léopard".to_string()]), consumer.finish());
+/// ```
+pub struct Input<'a, C, B = Vec<u8>> {
+    receive: Receive<'a, C>,
+    buffer: B,
+    /// The index of the first item that was not consumed.
+    /// The consumed items are only removed once they are most of the buffer.
+    start: usize,
+    ended: bool,
+}
+
+impl<'a, C, B> Input<'a, C, B>
+    where
+        C: AsRef<B::Slice>,
+        B: Buffer,
+{
+    /// Wraps the received chunks with an empty buffer.
+    pub fn new(receive: Receive<'a, C>) -> Self {
+        Input {
+            receive,
+            buffer: B::default(),
+            start: 0,
+            ended: false,
+        }
+    }
+
+    /// Receives another chunk into the buffer, returning `false` if the input has ended.
+    async fn fill(&mut self) -> bool {
+        if self.ended {
+            return false
+        }
+        match self.receive.next().await {
+            Some(chunk) => {
+                self.compact();
+                self.buffer.append(chunk.as_ref());
+                true
+            },
+            None => {
+                self.ended = true;
+                false
+            },
+        }
+    }
+
+    /// Removes the consumed items when there are at least as many as the unconsumed,
+    /// such that each item is moved a constant number of times on average.
+    fn compact(&mut self) {
+        if self.start > 0 && self.start >= B::slice_len(self.buffered()) {
+            self.buffer.remove_front(self.start);
+            self.start = 0;
+        }
+    }
+
+    /// Consumes the items until `end`, providing them.
+    fn split(&mut self, end: usize) -> B {
+        let taken = self.buffer.copy_range(self.start, end);
+        self.start = end;
+        taken
+    }
+
+    /// Provides the next item without consuming it.
+    ///
+    /// Finishes with `None` iff the input has ended.
+    pub async fn peek(&mut self) -> Option<B::Item> {
+        loop {
+            if let Some(item) = self.buffer.first(self.start) {
+                return Some(item)
+            }
+            if !self.fill().await {
+                return None
+            }
+        }
+    }
+
+    /// Consumes the next item.
+    ///
+    /// Finishes with `None` iff the input has ended.
+    pub async fn next(&mut self) -> Option<B::Item> {
+        let item = self.peek().await?;
+        self.start = self.buffer.boundary(self.start, 1)?;
+        Some(item)
+    }
+
+    /// Consumes the next `count` items.
+    ///
+    /// Finishes with `None` if the input ended with fewer items, which remain buffered.
+    pub async fn take(&mut self, count: usize) -> Option<B> {
+        loop {
+            if let Some(end) = self.buffer.boundary(self.start, count) {
+                return Some(self.split(end))
+            }
+            if !self.fill().await {
+                return None
+            }
+        }
+    }
+
+    /// Consumes the items before the delimiter, and the delimiter itself.
+    /// The delimiter is not included in the result.
+    ///
+    /// Finishes with `None` if the input ended without the delimiter, with the items remaining buffered.
+    pub async fn take_until(&mut self, delimiter: B::Item) -> Option<B> {
+        // Relative to the start, as compacting moves the items
+        let mut searched = 0;
+        loop {
+            if let Some((index, end)) = self.buffer.find(delimiter, self.start + searched) {
+                let taken = self.split(index);
+                self.start = end;
+                return Some(taken)
+            }
+            searched = B::slice_len(self.buffered());
+            if !self.fill().await {
+                return None
+            }
+        }
+    }
+
+    /// Consumes the tag iff the input begins with it.
+    ///
+    /// Finishes with `None` if the input does not begin with the tag, leaving the input unconsumed.
+    pub async fn expect(&mut self, tag: &B::Slice) -> Option<()> {
+        let len = B::slice_len(tag);
+        loop {
+            let buffered = self.buffered();
+            if B::slice_len(buffered) >= len {
+                if !B::slice_starts_with(buffered, tag) {
+                    return None
+                }
+                self.start += len;
+                return Some(())
+            }
+            if !B::slice_starts_with(tag, buffered) || !self.fill().await {
+                return None
+            }
+        }
+    }
+
+    /// Indicates that the input has ended, and all items have been consumed.
+    pub async fn is_end(&mut self) -> bool {
+        self.peek().await.is_none()
+    }
+
+    /// Consumes all remaining input, waiting for it to end.
+    pub async fn rest(&mut self) -> B {
+        while self.fill().await {}
+        self.buffer.remove_front(self.start);
+        self.start = 0;
+        mem::take(&mut self.buffer)
+    }
+
+    /// Provides the items that have been received but not yet consumed.
+    pub fn buffered(&self) -> &B::Slice {
+        self.buffer.slice_from(self.start)
+    }
+}
@@ -0,0 +1,123 @@
+use core::{
+    cell::{
+        Cell,
+        UnsafeCell,
+    },
+    pin::Pin,
+};
+
+use crate::{
+    Generator,
+    GeneratorStorage,
+    StorageHandle,
+};
+
+/// A fixed number of slots for generators, claimed by their iterators.
+/// Does not use an allocation.
+///
+/// Each slot is released once its iterator is dropped, such that it may be claimed again,
+/// as an arena for generators that are started and finished over time.
+/// The generators share a future type, such as from the same closure with different captures.
+///
+/// The pool is pinned to be claimed from, as a generator may remain in a slot
+/// after its iterator is forgotten, such as by [`GeneratorIterator::detach()`](crate::GeneratorIterator::detach).
+///
+/// ```
+/// # use std::pin::pin;
+/// # use remit::{Generator, GeneratorPool, Remit};
+/// // Each call provides the same type of generator
+/// let count = |to: usize| async move |remit: Remit<'_, usize>| {
+///     for i in 1..=to {
+///         remit.value(i).await;
+///     }
+/// };
+/// let pool = pin!(GeneratorPool::<_, _, 2>::new());
+/// let first = Generator::with_storage(pool.as_ref().claim().unwrap(), count(2));
+/// let second = Generator::with_storage(pool.as_ref().claim().unwrap(), count(3));
+/// assert!(pool.as_ref().claim().is_none());
+/// assert_eq!(vec![1, 2, 1, 2, 3], first.chain(second).collect::<Vec<_>>());
+/// assert_eq!(2, pool.available());
+/// ```
+///
+/// The pool can't be moved once pinned, even after the iterator is forgotten.
+/// ```compile_fail
+/// # use std::{future::Ready, mem, pin::Pin};
+/// # use remit::{Generator, GeneratorPool, Remit};
+/// let pool = Box::pin(GeneratorPool::<usize, Ready<()>, 1>::new());
+/// let iter = Generator::with_storage(pool.as_ref().claim().unwrap(), |_| std::future::ready(()));
+/// mem::forget(iter);
+/// // Fails to compile, because the pool is not `Unpin`.
+/// let moved = *Pin::into_inner(pool);
+/// ```
+pub struct GeneratorPool<T, P, const N: usize> {
+    slots: [Slot<T, P>; N],
+}
+
+struct Slot<T, P> {
+    claimed: Cell<bool>,
+    generator: UnsafeCell<Generator<T, P>>,
+}
+
+impl<T, P> Slot<T, P> {
+    const fn new() -> Self {
+        Slot {
+            claimed: Cell::new(false),
+            generator: UnsafeCell::new(Generator::new()),
+        }
+    }
+}
+
+impl<T, P, const N: usize> Default for GeneratorPool<T, P, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, P, const N: usize> GeneratorPool<T, P, N> {
+    /// Provides the pool with every slot available.
+    pub const fn new() -> Self {
+        GeneratorPool {
+            slots: [const { Slot::new() }; N],
+        }
+    }
+
+    /// Claims an available slot, to be used as the storage of a generator.
+    pub fn claim(self: Pin<&Self>) -> Option<PoolSlot<'_, T, P>> {
+        let slot = self.get_ref().slots.iter().find(|slot| !slot.claimed.get())?;
+        slot.claimed.set(true);
+        Some(PoolSlot(slot))
+    }
+
+    /// The number of slots that may be claimed.
+    pub fn available(&self) -> usize {
+        self.slots.iter().filter(|slot| !slot.claimed.get()).count()
+    }
+}
+
+/// A claimed slot of a [`GeneratorPool`], which is available again once dropped.
+///
+/// Used as the storage of [`Generator::with_storage()`], the iterator keeps it as the handle.
+pub struct PoolSlot<'a, T, P>(&'a Slot<T, P>);
+
+impl<T, P> Drop for PoolSlot<'_, T, P> {
+    fn drop(&mut self) {
+        self.0.claimed.set(false);
+    }
+}
+
+impl<T, P> StorageHandle<T, P> for PoolSlot<'_, T, P> {
+    unsafe fn release(self, raw: *mut Generator<T, P>) {
+        // SOUND: unsafe-fn, the generator is exclusive to this claim, and dropped in place
+        unsafe { (*raw).clear() };
+    }
+}
+
+// SOUND: the slot is exclusive while claimed, and the pool is borrowed for as long as the claim.
+// The generator is never moved, as the pool is pinned, and so is dropped in place if the claim is forgotten.
+unsafe impl<'a, T, P> GeneratorStorage<T, P> for PoolSlot<'a, T, P> {
+    type Handle = PoolSlot<'a, T, P>;
+
+    fn into_raw(self) -> (*mut Generator<T, P>, Self) {
+        (self.0.generator.get(), self)
+    }
+}
@@ -0,0 +1,111 @@
+//! Serialization of remitted values as a sequence, without collecting them.
+//!
+//! Each value is serialized as soon as it is remitted.
+//! The length of the sequence is only provided to the serializer when the iterator's
+//! `size_hint` is exact, which for a [`GeneratorIterator`](crate::GeneratorIterator)
+//! is only the case once the generator has completed.
+//!
+//! ```
+//! # use remit::{Generator, Remit};
+//! # use remit::serde::{Seq, SeqFn};
+//! # #[cfg(feature = "alloc")] {
+//! async fn squares(limit: u64, remit: Remit<'static, u64>) {
+//!     for i in 1..=limit {
+//!         remit.value(i * i).await;
+//!     }
+//! }
+//! let once = Seq::new(Generator::boxed(|remit| squares(4, remit)));
+//! assert_eq!("[1,4,9,16]", serde_json::to_string(&once).unwrap());
+//! assert!(serde_json::to_string(&once).is_err());
+//!
+//! let repeatable = SeqFn::new(|| Generator::boxed(|remit| squares(3, remit)));
+//! assert_eq!("[1,4,9]", serde_json::to_string(&repeatable).unwrap());
+//! assert_eq!("[1,4,9]", serde_json::to_string(&repeatable).unwrap());
+//! # }
+//! ```
+
+use core::cell::Cell;
+
+use ::serde::ser::{
+    Error,
+    Serialize,
+    SerializeSeq,
+    Serializer,
+};
+
+fn serialize_iter<S, I>(serializer: S, iter: I) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        I: Iterator,
+        I::Item: Serialize,
+{
+    let len = match iter.size_hint() {
+        (lower, Some(upper)) if lower == upper => Some(lower),
+        _ => None,
+    };
+    let mut seq = serializer.serialize_seq(len)?;
+    for item in iter {
+        seq.serialize_element(&item)?;
+    }
+    seq.end()
+}
+
+/// Serializes the values of an iterator, such as a [`GeneratorIterator`](crate::GeneratorIterator), as a sequence.
+///
+/// As the iterator is consumed, it can only be serialized once.
+/// Serializing again produces an error.
+pub struct Seq<I> {
+    iter: Cell<Option<I>>,
+}
+
+impl<I> Seq<I> {
+    /// Wraps the iterator to be serialized.
+    pub fn new(iter: I) -> Self {
+        Seq {
+            iter: Cell::new(Some(iter)),
+        }
+    }
+
+    /// Provides the iterator, if it has not yet been serialized.
+    pub fn into_inner(self) -> Option<I> {
+        self.iter.into_inner()
+    }
+}
+
+impl<I> Serialize for Seq<I>
+    where
+        I: Iterator,
+        I::Item: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.iter.take() {
+            Some(iter) => serialize_iter(serializer, iter),
+            None => Err(S::Error::custom("the sequence was already serialized")),
+        }
+    }
+}
+
+/// Serializes the values of an iterator created for each serialization, as a sequence.
+pub struct SeqFn<F> {
+    factory: F,
+}
+
+impl<F> SeqFn<F> {
+    /// Wraps the function creating the iterator to be serialized.
+    pub fn new(factory: F) -> Self {
+        SeqFn {
+            factory,
+        }
+    }
+}
+
+impl<F, I> Serialize for SeqFn<F>
+    where
+        F: Fn() -> I,
+        I: Iterator,
+        I::Item: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_iter(serializer, (self.factory)())
+    }
+}
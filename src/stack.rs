@@ -0,0 +1,97 @@
+use core::{
+    future::Future,
+    ops::AsyncFnOnce,
+    pin::Pin,
+};
+
+use crate::{
+    Generator,
+    GeneratorIterator,
+    Remit,
+    StaticCall,
+};
+
+/// A generator that has not yet started, along with the storage for its future.
+/// Stack based (does not use an allocation).
+///
+/// Until it is pinned, it may be moved freely, such as returned from a function.
+/// Iterating a pinned `StackGenerator` starts the generator.
+///
+/// Once started, iterating again continues from where a detached iterator left off,
+/// as with [`Generator::resume_iter()`].
+///
+/// ```
+/// # use std::{future::Future, pin::pin};
+/// # use remit::{Remit, StackGenerator};
+/// fn countdown(from: usize) -> StackGenerator<usize, impl Sized, impl Future<Output=()>> {
+///     StackGenerator::new(async move |remit: Remit<'_, usize>| {
+///         for i in (1..=from).rev() {
+///             remit.value(i).await;
+///         }
+///     })
+/// }
+///
+/// let unstarted = countdown(3);
+/// let moved = [unstarted];
+/// let [gen] = moved;
+/// assert_eq!(vec![3, 2, 1], pin!(gen).into_iter().collect::<Vec<_>>());
+///
+/// let mut gen = pin!(countdown(4));
+/// assert!(!gen.is_started());
+/// let mut iter = gen.as_mut().into_iter();
+/// assert_eq!(Some(4), iter.next());
+/// iter.detach();
+/// assert!(gen.is_started());
+/// assert_eq!(vec![3, 2, 1], gen.into_iter().collect::<Vec<_>>());
+/// ```
+pub struct StackGenerator<T, G, P> {
+    gen: Option<G>,
+    start: fn(G, Remit<'static, T>) -> P,
+    storage: Generator<T, P>,
+}
+
+impl<T, G, P> StackGenerator<T, G, P> {
+    /// Holds the generator, without starting it.
+    ///
+    /// The same lifetime relaxation as [`Generator::of()`] applies,
+    /// such that the generator must accept any lifetime.
+    pub fn new(gen: G) -> Self
+        where
+            // insures fn is not implemented only for 'static
+            G: for<'a> AsyncFnOnce(Remit<'a, T>),
+            // insures P is properly defined, even if it actually has a lifetime
+            G: StaticCall<(Remit<'static, T>, ), Output=P>,
+    {
+        StackGenerator {
+            gen: Some(gen),
+            start: |gen, remit| gen.call_static((remit, )),
+            storage: Generator::new(),
+        }
+    }
+
+    /// Indicates that the generator has been started, by having been iterated.
+    pub fn is_started(&self) -> bool {
+        self.gen.is_none()
+    }
+}
+
+impl<'s, T, G, P> IntoIterator for Pin<&'s mut StackGenerator<T, G, P>>
+    where
+        P: Future<Output=()>,
+{
+    type Item = T;
+    type IntoIter = GeneratorIterator<'s, T, P>;
+
+    fn into_iter(self) -> GeneratorIterator<'s, T, P> {
+        // SOUND: Pin passthrough; only `storage` is inner-pinned.
+        // `gen` and `start` are never pinned.
+        let inner = unsafe { self.get_unchecked_mut() };
+        let Some(gen) = inner.gen.take() else {
+            // SOUND: see above
+            return unsafe { Pin::new_unchecked(&mut inner.storage) }.resume_iter()
+        };
+        let start = inner.start;
+        // SOUND: see above, and the trait-guard in `new` is where the lifetime protection is
+        unsafe { Generator::start(&mut inner.storage, |remit| start(gen, remit)) }
+    }
+}
@@ -0,0 +1,394 @@
+use core::{
+    arch::asm,
+    cell::Cell,
+    future::Future,
+    marker::PhantomPinned,
+    pin::Pin,
+    ptr,
+    task::{
+        Context,
+        Poll,
+    },
+};
+
+use std::{
+    any::Any,
+    boxed::Box,
+    panic::{
+        self,
+        AssertUnwindSafe,
+    },
+    process,
+    thread,
+};
+
+use crate::{
+    Generator,
+    GeneratorIterator,
+    Remit,
+};
+
+/// The size of the separate stack of each stackful generator, not including its guard page.
+///
+/// The stack is reserved when the generator is first polled, with pages only committed once used.
+/// Overflowing the stack faults on the guard page, aborting the process.
+pub const STACKFUL_STACK_SIZE: usize = 1 << 20;
+
+thread_local! {
+    /// The innermost stackful generator that is running on this thread.
+    static CURRENT: Cell<*mut Switch> = const { Cell::new(ptr::null_mut()) };
+}
+
+/// The stacks switched between, when running a stackful generator.
+struct Switch {
+    /// The saved stack of what polled the generator, while the generator is running.
+    caller: *mut u8,
+    /// The saved stack of the generator, while it is suspended.
+    coroutine: *mut u8,
+    /// The exchange of the generator's `Remit`, to check that `value_sync` is for this generator.
+    exchange: *const (),
+    /// The generator is only resumed to unwind it.
+    cancel: bool,
+}
+
+/// The payload used to unwind a stackful generator that was dropped before finishing.
+struct Cancelled;
+
+/// A stack reserved with a guard page below it.
+struct Stack {
+    base: *mut u8,
+    len: usize,
+}
+
+impl Stack {
+    fn new(size: usize) -> Self {
+        // SOUND: FFI, with the mapping checked before use
+        unsafe {
+            let page = libc::sysconf(libc::_SC_PAGESIZE) as usize;
+            let len = size.div_ceil(page) * page + page;
+            let base = libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_NORESERVE | libc::MAP_STACK,
+                -1,
+                0,
+            );
+            if base == libc::MAP_FAILED {
+                panic!("failed to reserve the stack of a stackful generator");
+            }
+            // The stack grows down, overflowing into the guard page
+            if libc::mprotect(base, page, libc::PROT_NONE) != 0 {
+                libc::munmap(base, len);
+                panic!("failed to protect the stack of a stackful generator");
+            }
+            Stack {
+                base: base.cast(),
+                len,
+            }
+        }
+    }
+
+    /// Provides the stack pointer to switch to, such that the entry is started.
+    fn prepare(&self, entry: extern "C" fn() -> !) -> *mut u8 {
+        let top = (self.base as usize + self.len) & !15;
+        let sp = (top - 32) as *mut usize;
+        // SOUND: within the mapping, and aligned, as the mapping is page-aligned
+        unsafe {
+            // The callee-saved registers restored by `switch`, with the frame-pointer chain ending here
+            sp.write(0);
+            sp.add(1).write(0);
+            // Where `switch` returns to
+            sp.add(2).write(entry as usize);
+            // Where the entry would return to, ending backtraces here
+            sp.add(3).write(0);
+        }
+        sp.cast()
+    }
+}
+
+impl Drop for Stack {
+    fn drop(&mut self) {
+        // SOUND: FFI, the mapping is no longer used
+        unsafe { libc::munmap(self.base.cast(), self.len) };
+    }
+}
+
+#[inline(never)]
+/// Saves the current stack pointer into `save`, and resumes the stack at `to`.
+///
+/// The saved frame is rbx, rbp, and the return address.
+///
+/// # Safety
+///
+/// `to` must have been saved by `switch`, or prepared by [`Stack::prepare()`].
+unsafe fn switch(save: *mut *mut u8, to: *mut u8) {
+    // NEED: the callee-saved registers that can't be operands are saved on the stack,
+    // with the rest declared as clobbered
+    asm!(
+        "lea rax, [rip + 2f]",
+        "push rax",
+        "push rbp",
+        "push rbx",
+        "mov [rdi], rsp",
+        "mov rsp, rsi",
+        "pop rbx",
+        "pop rbp",
+        "ret",
+        "2:",
+        in("rdi") save,
+        in("rsi") to,
+        out("r12") _,
+        out("r13") _,
+        out("r14") _,
+        out("r15") _,
+        clobber_abi("C"),
+    );
+}
+
+/// Runs the generator until it suspends or finishes.
+///
+/// # Safety
+///
+/// The switch must be of a started generator that has not finished.
+unsafe fn resume(current: *mut Switch) {
+    let previous = CURRENT.replace(current);
+    switch(&mut (*current).caller, (*current).coroutine);
+    CURRENT.set(previous);
+}
+
+/// Returns to what polled the generator.
+///
+/// # Safety
+///
+/// Must be called on the stack of the generator, which is running.
+unsafe fn suspend(current: *mut Switch) {
+    switch(&mut (*current).coroutine, (*current).caller);
+}
+
+/// The future of a stackful generator, which runs on a separate stack.
+///
+/// The generator is a plain closure, which may call [`Remit::value_sync()`] from anywhere in its call stack,
+/// such as from the callback of an API that only provides internal iteration.
+#[repr(C)]
+// NEED: `switch` is first, such that a ptr to it is a ptr to the Stackful
+pub struct Stackful<T, F> {
+    switch: Switch,
+    stack: Option<Stack>,
+    start: Option<(F, Remit<'static, T>)>,
+    panic: Option<Box<dyn Any + Send>>,
+    finished: bool,
+    _pin: PhantomPinned,
+}
+
+impl<T, F> Stackful<T, F> {
+    fn new(gen: F, remit: Remit<'static, T>) -> Self {
+        Stackful {
+            switch: Switch {
+                caller: ptr::null_mut(),
+                coroutine: ptr::null_mut(),
+                exchange: remit.0.exchange().cast(),
+                cancel: false,
+            },
+            stack: None,
+            start: Some((gen, remit)),
+            panic: None,
+            finished: false,
+            _pin: PhantomPinned,
+        }
+    }
+}
+
+/// The start of the generator's stack.
+extern "C" fn entry<T, F: FnOnce(Remit<'static, T>)>() -> ! {
+    let current = CURRENT.get();
+    let stackful: *mut Stackful<T, F> = current.cast();
+    // SOUND: (valid-ptr) repr(C) with switch first, set by resume for this generator
+    // SOUND: (&mut exclusive) the poller only touches the Stackful while the generator is suspended
+    unsafe {
+        if let Some((gen, remit)) = (*stackful).start.take() {
+            if let Err(panic) = panic::catch_unwind(AssertUnwindSafe(|| gen(remit))) {
+                (*stackful).panic = Some(panic);
+            }
+        }
+        (*stackful).finished = true;
+        suspend(current);
+    }
+    // The finished generator is never resumed
+    process::abort()
+}
+
+impl<T, F: FnOnce(Remit<'static, T>)> Future for Stackful<T, F> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        // SOUND: Pin passthrough; the generator's stack refers to the Stackful, which is never moved
+        let this: *mut Self = unsafe { self.get_unchecked_mut() };
+        // SOUND: only accessed through the ptr, as the generator's stack does the same
+        unsafe {
+            if (*this).finished {
+                return Poll::Ready(())
+            }
+            if (*this).stack.is_none() {
+                let stack = Stack::new(STACKFUL_STACK_SIZE);
+                (*this).switch.coroutine = stack.prepare(entry::<T, F>);
+                (*this).stack = Some(stack);
+            }
+            resume(this.cast());
+            if !(*this).finished {
+                return Poll::Pending
+            }
+            (*this).stack = None;
+            if let Some(panic) = (*this).panic.take() {
+                panic::resume_unwind(panic)
+            }
+        }
+        Poll::Ready(())
+    }
+}
+
+impl<T, F> Drop for Stackful<T, F> {
+    fn drop(&mut self) {
+        if self.finished || self.stack.is_none() {
+            return
+        }
+        // The generator unwinds from where it was suspended, and is then finished
+        self.switch.cancel = true;
+        let this: *mut Self = self;
+        // NEED: the stack is only unmapped once nothing on it is live,
+        // with `finished` read through the ptr, as the generator's stack writes it
+        //
+        loop {
+            // SOUND: started, and not finished
+            unsafe { resume(this.cast()) };
+            // SOUND: (valid-ptr) from self
+            if unsafe { (*this).finished } {
+                break
+            }
+        }
+        // SOUND: see above
+        unsafe { (*this).panic = None };
+    }
+}
+
+impl<T> Remit<'_, T> {
+    /// Remits the value to the iterator, suspending the whole call stack of a stackful generator
+    /// until the iterator has taken the value.
+    ///
+    /// Does not need to be awaited, such that it may be called from a callback.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not the `Remit` of the stackful generator that is running.
+    ///
+    /// # Cancellation
+    ///
+    /// Dropping the iterator of an unfinished generator unwinds its stack from where it was suspended.
+    /// While unwinding, such as in the `Drop` of a guard, values are discarded without suspending.
+    /// ```
+    /// # use std::{cell::Cell, pin::pin};
+    /// # use remit::{Generator, Remit};
+    /// struct Guard<'a>(&'a Remit<'a, u8>, &'a Cell<bool>);
+    ///
+    /// impl Drop for Guard<'_> {
+    ///     fn drop(&mut self) {
+    ///         self.0.value_sync(99);
+    ///         self.1.set(true);
+    ///     }
+    /// }
+    ///
+    /// let finished = Cell::new(false);
+    /// let mut storage = pin!(Generator::new());
+    /// let mut iter = storage.as_mut().stackful(|remit: Remit<'_, u8>| {
+    ///     let _guard = Guard(&remit, &finished);
+    ///     remit.value_sync(1);
+    ///     remit.value_sync(2);
+    /// });
+    /// assert_eq!(Some(1), iter.next());
+    /// drop(iter);
+    /// assert!(finished.get());
+    /// ```
+    ///
+    /// ```
+    /// # use std::pin::pin;
+    /// # use remit::{Generator, Remit};
+    /// struct Tree(&'static str, Vec<Tree>);
+    ///
+    /// impl Tree {
+    ///     fn visit(&self, f: &mut dyn FnMut(&str)) {
+    ///         f(self.0);
+    ///         for child in &self.1 {
+    ///             child.visit(f);
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let tree = Tree("a", vec![Tree("b", vec![Tree("c", vec![])]), Tree("d", vec![])]);
+    /// let mut visited = vec![];
+    /// let mut storage = pin!(Generator::new());
+    /// let mut iter = storage.as_mut().stackful(|remit: Remit<'_, String>| {
+    ///     tree.visit(&mut |name| remit.value_sync(name.to_uppercase()));
+    /// });
+    /// // Lazily visited, as each value suspends the visit
+    /// visited.extend(iter.by_ref().take(2));
+    /// assert_eq!(vec!["A", "B"], visited);
+    /// visited.extend(iter);
+    /// assert_eq!(vec!["A", "B", "C", "D"], visited);
+    /// ```
+    pub fn value_sync(&self, value: T) {
+        let current = CURRENT.get();
+        // SOUND: (valid-ptr) only set while the generator is running, by resume
+        if current.is_null() || unsafe { (*current).exchange } != self.0.exchange().cast() {
+            panic!("value_sync requires the Remit of the running stackful generator");
+        }
+        // SOUND: see above
+        if unsafe { (*current).cancel } {
+            // NEED: suspending again would leave the rest of the unwind on a stack that is unmapped
+            if !thread::panicking() {
+                panic::resume_unwind(Box::new(Cancelled))
+            }
+            return
+        }
+        // SOUND: non-public field, valid at instantiation
+        // SOUND: self not dropped
+        //
+        // NEED: use-after-free prevention of value-exchange
+        if unsafe { self.0.strong() } {
+            self.0.push(value);
+        }
+        // SOUND: running on the generator's stack, as it is the running generator
+        unsafe { suspend(current) };
+        // SOUND: see above
+        if unsafe { (*current).cancel } {
+            panic::resume_unwind(Box::new(Cancelled))
+        }
+    }
+}
+
+impl<T, F> Generator<T, Stackful<T, F>> {
+    #[allow(clippy::needless_lifetimes)]
+    /// Takes the pinned storage and a stackful generator, and provides an iterator.
+    /// The generator runs on a separate stack of [`STACKFUL_STACK_SIZE`].
+    ///
+    /// The generator is a closure, rather than async, and remits with [`Remit::value_sync()`].
+    /// The same lifetime relaxation as [`Generator::of()`] applies.
+    pub fn stackful<'s>(self: Pin<&'s mut Self>, gen: F) -> GeneratorIterator<'s, T, Stackful<T, F>>
+        where
+            // insures fn is not implemented only for 'static
+            F: for<'a> FnOnce(Remit<'a, T>),
+    {
+        // SOUND: Pin passthrough; only `future` is inner-pinned.
+        // The trait-guard is where the lifetime protection is.
+        unsafe { Generator::start(self.get_unchecked_mut(), |remit| Stackful::new(gen, remit)) }
+    }
+
+    /// Uses an allocation so that the iterator of a stackful generator does not need to be borrowed.
+    ///
+    /// The generator only needs to be valid for `'static`; it does not need to be valid for all lifetimes.
+    pub fn boxed_stackful(gen: F) -> GeneratorIterator<'static, T, Stackful<T, F>>
+        where
+            F: FnOnce(Remit<'static, T>),
+    {
+        Generator::boxed(|remit| Stackful::new(gen, remit))
+    }
+}
@@ -0,0 +1,220 @@
+use core::{
+    mem::{
+        self,
+        MaybeUninit,
+    },
+    ops::AsyncFnOnce,
+    pin::Pin,
+};
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+use crate::{
+    Generator,
+    GeneratorIterator,
+    Remit,
+    StaticCall,
+};
+
+/// Storage that holds a [`Generator`] at a stable address, for use with [`Generator::with_storage()`].
+///
+/// Implemented for pinned references, including `Pin<&'static mut Generator<T, P>>` for a static slot,
+/// for a pinned box owned by the iterator,
+/// and for a slot claimed from a [`GeneratorPool`].
+/// An arena or bump allocator may implement this for its own handle,
+/// provided it never moves the storage.
+///
+/// [`Generator::of()`] and its parameterized forms are the same as using a pinned reference.
+/// [`Generator::boxed()`] instead uses its own single allocation,
+/// so that a `Remit` moved out by a generator valid only for `'static` keeps the storage alive.
+/// Shared ownership, such as `Rc` or `Arc`, can't provide the exclusive access needed to poll the future.
+///
+/// # Safety
+///
+/// The pointer from [`GeneratorStorage::into_raw()`] must be valid, exclusive, and not moved,
+/// for as long as the implementing type's lifetime,
+/// or, when the handle is [owned](StorageHandle::OWNED), until the handle is released.
+/// The [`Generator`] must not be accessed other than through the pointer during that time.
+pub unsafe trait GeneratorStorage<T, P>: Sized {
+    /// Kept by the iterator, to release the storage once the iterator is done with it.
+    type Handle: StorageHandle<T, P>;
+
+    /// Provides the pinned generator, along with the handle that releases it.
+    fn into_raw(self) -> (*mut Generator<T, P>, Self::Handle);
+}
+
+/// Releases the storage of a generator that is owned by its iterator, such as by freeing it.
+///
+/// The iterator keeps the handle without an allocation,
+/// so it may be at most the size and alignment of two pointers, such as a reference and an index.
+/// Larger state may be kept alongside the generator, in the storage itself.
+pub trait StorageHandle<T, P>: Sized {
+    /// Whether the iterator owns the storage, such that dropping or [detaching](GeneratorIterator::detach())
+    /// the iterator releases it.
+    ///
+    /// When `false`, the handle is dropped immediately, as the storage outlives the iterator,
+    /// and a detached iterator may be resumed.
+    const OWNED: bool = true;
+
+    /// Releases the storage, once the iterator is done with it.
+    ///
+    /// # Safety
+    ///
+    /// Only called once, with the pointer provided along with this handle.
+    /// The generator is still valid when this is called, such that it may be dropped.
+    unsafe fn release(self, raw: *mut Generator<T, P>);
+}
+
+/// The handle of storage that outlives the iterator.
+impl<T, P> StorageHandle<T, P> for () {
+    const OWNED: bool = false;
+
+    unsafe fn release(self, _: *mut Generator<T, P>) {}
+}
+
+// SOUND: the borrow is exclusive and pinned for 'a
+unsafe impl<T, P> GeneratorStorage<T, P> for Pin<&mut Generator<T, P>> {
+    type Handle = ();
+
+    fn into_raw(self) -> (*mut Generator<T, P>, ()) {
+        // SOUND: Pin passthrough, as the trait requires the pointer not be moved
+        (unsafe { self.get_unchecked_mut() }, ())
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// The handle of a pinned box owned by the iterator, which frees it.
+pub struct BoxHandle;
+
+#[cfg(feature = "alloc")]
+impl<T, P> StorageHandle<T, P> for BoxHandle {
+    unsafe fn release(self, raw: *mut Generator<T, P>) {
+        // SOUND: unsafe-fn, only ever called once with the ptr from into_raw
+        drop(unsafe { Box::from_raw(raw) })
+    }
+}
+
+#[cfg(feature = "alloc")]
+// SOUND: the box is only freed by the handle, and never moved out of
+unsafe impl<T, P> GeneratorStorage<T, P> for Pin<Box<Generator<T, P>>> {
+    type Handle = BoxHandle;
+
+    fn into_raw(self) -> (*mut Generator<T, P>, BoxHandle) {
+        // SOUND: Pin passthrough, as the trait requires the pointer not be moved
+        (Box::into_raw(unsafe { Pin::into_inner_unchecked(self) }), BoxHandle)
+    }
+}
+
+/// Where the handle is kept, with its type erased.
+type Inline = MaybeUninit<[*mut (); 2]>;
+
+/// Storage released by an iterator when dropped, with the type of its handle erased.
+pub(crate) struct Storage {
+    raw: *mut (),
+    handle: Inline,
+    release: unsafe fn(*mut (), *mut Inline),
+}
+
+impl Storage {
+    /// Keeps the handle to be released, if the iterator owns the storage.
+    fn of<H: StorageHandle<T, P>, T, P>(raw: *mut Generator<T, P>, handle: H) -> Option<Self> {
+        if !H::OWNED {
+            return None
+        }
+        const {
+            assert!(
+                mem::size_of::<H>() <= mem::size_of::<Inline>() && mem::align_of::<H>() <= mem::align_of::<Inline>(),
+                "a StorageHandle may be at most the size and alignment of two pointers",
+            )
+        };
+        let mut inline = Inline::uninit();
+        // SOUND: fits, as asserted above
+        unsafe { inline.as_mut_ptr().cast::<H>().write(handle) };
+        Some(Storage {
+            raw: raw.cast(),
+            handle: inline,
+            release: release::<H, T, P>,
+        })
+    }
+}
+
+/// Releases the erased storage with its handle.
+///
+/// # Safety
+///
+/// See [`StorageHandle::release()`], with the handle written by [`Storage::of()`].
+unsafe fn release<H: StorageHandle<T, P>, T, P>(raw: *mut (), handle: *mut Inline) {
+    // SOUND: unsafe-fn, the handle is only read once, and the ptr was cast from the same storage
+    unsafe { handle.cast::<H>().read().release(raw.cast()) }
+}
+
+impl Drop for Storage {
+    fn drop(&mut self) {
+        // SOUND: only created in Storage::of, and dropped once by the iterator
+        unsafe { (self.release)(self.raw, &mut self.handle) }
+    }
+}
+
+impl<T, P> Generator<T, P> {
+    /// Takes the storage and the generator and provides an iterator.
+    ///
+    /// [`Generator::of()`] is a preset for using a pinned reference as the storage.
+    /// The same lifetime relaxation applies, such that the generator must accept any lifetime.
+    ///
+    /// When the storage is owned by the iterator, such as a pinned box,
+    /// dropping or [detaching](GeneratorIterator::detach()) the iterator releases it.
+    ///
+    /// ```
+    /// # use std::pin::pin;
+    /// # use remit::{Generator, GeneratorIterator, Remit};
+    /// async fn gen(remit: Remit<'_, usize>) {
+    ///     for i in 1..=3 {
+    ///         remit.value(i).await;
+    ///     }
+    /// }
+    /// let mut storage = pin!(Generator::new());
+    /// assert_eq!(vec![1, 2, 3], Generator::with_storage(storage.as_mut(), gen).collect::<Vec<_>>());
+    ///
+    /// # #[cfg(feature = "alloc")] {
+    /// fn iter() -> GeneratorIterator<'static, usize, impl std::future::Future<Output=()>> {
+    ///     Generator::with_storage(Box::pin(Generator::new()), gen)
+    /// }
+    /// assert_eq!(vec![1, 2], iter().take(2).collect::<Vec<_>>());
+    /// # }
+    /// ```
+    pub fn with_storage<'s, S, G>(
+        storage: S,
+        gen: G,
+    ) -> GeneratorIterator<'s, T, P>
+        where
+            S: GeneratorStorage<T, P> + 's,
+            // insures fn is not implemented only for 'static
+            G: for<'a> AsyncFnOnce(Remit<'a, T>),
+            // insures P is properly defined, even if it actually has a lifetime
+            G: StaticCall<(Remit<'static, T>, ), Output=P>,
+    {
+        // SOUND: the trait-guard is where the lifetime protection is
+        unsafe { Generator::start_in(storage, |remit| gen.call_static((remit, ))) }
+    }
+
+    /// Starts the generator in the storage, with the iterator keeping its handle.
+    ///
+    /// # Safety
+    ///
+    /// The generator must be valid for any lifetime provided to the `Remit`.
+    pub(crate) unsafe fn start_in<'s, S>(
+        storage: S,
+        gen: impl FnOnce(Remit<'static, T>) -> P,
+    ) -> GeneratorIterator<'s, T, P>
+        where
+            S: GeneratorStorage<T, P> + 's,
+    {
+        let (raw, handle) = storage.into_raw();
+        // SOUND: the storage is pinned for 's, or until released by the iterator,
+        // and the generator is valid per the unsafe-fn
+        let mut iter = unsafe { Generator::start(raw, gen) };
+        iter._storage = Storage::of(raw, handle);
+        iter
+    }
+}
@@ -0,0 +1,288 @@
+//! Generators whose values are consumed from another context,
+//! such as an interrupt handler or a second core.
+//!
+//! The values are exchanged through a single slot guarded by [`critical_section`],
+//! instead of relying on the iterator and generator being on the same thread.
+//! A [`Driver`] polls the generator, such as in the main loop,
+//! while a [`Receiver`] may be shared to take the values.
+//! Either may be the one moved into the other context.
+//!
+//! Remitting a value waits until the slot is empty, such that at most one value is pending.
+//!
+//! The generator uses this module's [`Remit`], rather than the crate's [`Remit`](crate::Remit).
+//! The crate's exchange is accessed without synchronization,
+//! relying on its iterator and generator being in the same context, which is why neither is `Send`,
+//! and it buffers extra values in an allocation.
+//! Guarding it would add a critical section to every generator,
+//! so the exchange here is instead a single slot that is always guarded.
+//!
+//! ```
+//! # use std::{pin::pin, thread};
+//! # use remit::sync::{Remit, Storage};
+//! async fn gen(remit: Remit<'_, usize>) {
+//!     for i in 1..=4 {
+//!         remit.value(i).await;
+//!     }
+//! }
+//! let mut storage = pin!(Storage::new());
+//! let (mut driver, receiver) = storage.as_mut().split(gen);
+//! let received = thread::scope(|scope| {
+//!     let consumer = scope.spawn(|| {
+//!         let mut received = vec![];
+//!         while !receiver.is_finished() {
+//!             received.extend(receiver.try_next());
+//!         }
+//!         received
+//!     });
+//!     while driver.poll().is_pending() {}
+//!     consumer.join().unwrap()
+//! });
+//! assert_eq!(vec![1, 2, 3, 4], received);
+//! ```
+//!
+//! When the values are consumed in the same context, the driver may be iterated directly.
+//! ```
+//! # use std::pin::pin;
+//! # use remit::sync::{Remit, Storage};
+//! # async fn gen(remit: Remit<'_, usize>) {
+//! #     for i in 1..=4 {
+//! #         remit.value(i).await;
+//! #     }
+//! # }
+//! let mut storage = pin!(Storage::new());
+//! let (driver, _) = storage.as_mut().split(gen);
+//! assert_eq!(vec![1, 2, 3, 4], driver.collect::<Vec<_>>());
+//! ```
+
+use core::{
+    cell::{
+        RefCell,
+        UnsafeCell,
+    },
+    future::{
+        poll_fn,
+        Future,
+    },
+    marker::{
+        PhantomData,
+        PhantomPinned,
+    },
+    ops::AsyncFnOnce,
+    pin::Pin,
+    task::Poll,
+};
+
+use critical_section::Mutex;
+
+use crate::{
+    poll_slot,
+    StaticCall,
+};
+
+/// The value in transit, along with whether the generator has finished.
+struct State<T> {
+    value: Option<T>,
+    finished: bool,
+}
+
+/// The storage for a generator, to be pinned, with an exchange guarded by a critical section.
+pub struct Storage<T, P> {
+    // NEED: dropped before the state, as the future refers to it
+    future: UnsafeCell<Option<P>>,
+    state: Mutex<RefCell<State<T>>>,
+    _pin: PhantomPinned,
+}
+
+impl<T, P> Default for Storage<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, P> Storage<T, P> {
+    /// Provides the storage to be pinned, usable in a const context.
+    pub const fn new() -> Self {
+        Storage {
+            future: UnsafeCell::new(None),
+            state: Mutex::new(RefCell::new(State {
+                value: None,
+                finished: false,
+            })),
+            _pin: PhantomPinned,
+        }
+    }
+
+    #[allow(clippy::needless_lifetimes)]
+    /// Takes the pinned storage and the generator,
+    /// and provides the driver that polls it along with the receiver of its values.
+    ///
+    /// The same lifetime relaxation as [`Generator::of()`](crate::Generator::of()) applies,
+    /// such that the generator must accept any lifetime.
+    pub fn split<'s, G>(
+        self: Pin<&'s mut Self>,
+        gen: G,
+    ) -> (Driver<'s, T, P>, Receiver<'s, T>)
+        where
+            // insures fn is not implemented only for 'static
+            G: for<'a> AsyncFnOnce(Remit<'a, T>),
+            // insures P is properly defined, even if it actually has a lifetime
+            G: StaticCall<(Remit<'static, T>, ), Output=P>,
+    {
+        // SOUND: Pin passthrough; only `future` is inner-pinned.
+        // `future` only ever gets replaced, or dropped in place
+        let inner = unsafe { self.get_unchecked_mut() };
+        *inner.future.get_mut() = None;
+        *inner.state.get_mut().get_mut() = State {
+            value: None,
+            finished: false,
+        };
+        let inner: &'s Self = inner;
+        let remit = Remit {
+            state: &inner.state,
+            // This becomes 'static, and the trait-guard is where the real protection is
+            _lifetime: PhantomData,
+        };
+        // SOUND: the future is only accessed through the single driver,
+        // and the state only through the mutex.
+        //
+        // NEED: unsafe-cell lets the shared-references to the state not conflict with the exclusive future
+        unsafe { *inner.future.get() = Some(gen.call_static((remit, ))) };
+        let driver = Driver {
+            done: false,
+            state: &inner.state,
+            future: inner.future.get(),
+        };
+        (driver, Receiver(&inner.state))
+    }
+}
+
+/// Allows a generator to provide values to a [`Receiver`] in another context.
+pub struct Remit<'a, T> {
+    state: *const Mutex<RefCell<State<T>>>,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+// SOUND: the state is only accessed through the mutex, as with a shared-reference
+unsafe impl<T: Send> Send for Remit<'_, T> {}
+
+// SOUND: see above
+unsafe impl<T: Send> Sync for Remit<'_, T> {}
+
+impl<T> Remit<'_, T> {
+    /// Remits the value to the receiver.
+    ///
+    /// The provided future finishes once the value is placed in the empty slot,
+    /// such that the generator may prepare the next value while this one is pending.
+    pub fn value(&self, value: T) -> impl Future<Output=()> + '_ {
+        let mut value = Some(value);
+        poll_fn(move |_| critical_section::with(|cs| {
+            // SOUND: (use-after-free) the state outlives the future holding the Remit,
+            // as the driver drops the future in place before the storage's borrow ends
+            let mut state = unsafe { &*self.state }.borrow_ref_mut(cs);
+            if state.value.is_some() {
+                return Poll::Pending
+            }
+            state.value = value.take();
+            Poll::Ready(())
+        }))
+    }
+}
+
+/// Polls the generator, placing its values in the exchange.
+///
+/// Dropping the driver drops the generator, and marks the receiver as finished.
+pub struct Driver<'a, T, P> {
+    done: bool,
+    state: &'a Mutex<RefCell<State<T>>>,
+    future: *mut Option<P>,
+}
+
+// SOUND: the future is only accessed through the driver, and the state through the mutex.
+unsafe impl<T: Send, P: Send> Send for Driver<'_, T, P> {}
+
+impl<T, P: Future<Output=()>> Driver<'_, T, P> {
+    /// Polls the generator, providing `Ready` once it has finished.
+    ///
+    /// The generator is pending while the slot holds a value that was not received.
+    pub fn poll(&mut self) -> Poll<()> {
+        if self.done {
+            return Poll::Ready(())
+        }
+        // SOUND: (pinning) the ptr was created from the pinned storage, borrowed for the lifetime.
+        // SOUND: (&mut exclusive) only the driver touches the future, through an exclusive-borrow.
+        if let Poll::Ready(()) = unsafe { poll_slot(self.future) } {
+            self.done = true;
+            self.finish();
+            return Poll::Ready(())
+        }
+        Poll::Pending
+    }
+
+    /// Indicates that the generator has finished.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+impl<T, P: Future<Output=()>> Iterator for Driver<'_, T, P> {
+    type Item = T;
+
+    /// Takes the pending value, polling the generator when there is none.
+    ///
+    /// As with a [`GeneratorIterator`](crate::GeneratorIterator),
+    /// provides `None` if the generator awaited without remitting a value,
+    /// or if a [`Receiver`] took it first.
+    fn next(&mut self) -> Option<T> {
+        if let Some(value) = self.pending() {
+            return Some(value)
+        }
+        let _ = self.poll();
+        self.pending()
+    }
+}
+
+impl<T, P> Driver<'_, T, P> {
+    fn pending(&self) -> Option<T> {
+        critical_section::with(|cs| self.state.borrow_ref_mut(cs).value.take())
+    }
+
+    fn finish(&self) {
+        critical_section::with(|cs| self.state.borrow_ref_mut(cs).finished = true);
+    }
+}
+
+impl<T, P> Drop for Driver<'_, T, P> {
+    fn drop(&mut self) {
+        // SOUND: see Driver::poll(), with the future dropped in place
+        unsafe { *self.future = None };
+        self.finish();
+    }
+}
+
+/// Takes the values of a generator polled by a [`Driver`].
+///
+/// May be copied, or shared with an interrupt handler.
+pub struct Receiver<'a, T>(&'a Mutex<RefCell<State<T>>>);
+
+impl<T> Clone for Receiver<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Receiver<'_, T> {}
+
+impl<T> Receiver<'_, T> {
+    /// Takes the pending value, if any.
+    pub fn try_next(&self) -> Option<T> {
+        critical_section::with(|cs| self.0.borrow_ref_mut(cs).value.take())
+    }
+
+    /// Indicates that the generator has finished, or was dropped, and all values were taken.
+    pub fn is_finished(&self) -> bool {
+        critical_section::with(|cs| {
+            let state = self.0.borrow_ref(cs);
+            state.finished && state.value.is_none()
+        })
+    }
+}
@@ -0,0 +1,383 @@
+//! Generators run on a dedicated thread, with their values handed off through a bounded channel.
+//!
+//! The generator is polled by a blocking executor on its own thread,
+//! such that it may prefetch values while they are being consumed,
+//! and may await other futures that wake it.
+//! The iterator blocks in [`Iterator::next()`] until a value is available.
+//!
+//! ```
+//! # use remit::thread::{self, Remit};
+//! async fn squares(remit: Remit<u64>) {
+//!     for i in 1..=4 {
+//!         remit.value(i * i).await;
+//!     }
+//! }
+//! assert_eq!(vec![1, 4, 9, 16], thread::spawn(squares).collect::<Vec<_>>());
+//! ```
+//!
+//! [`Remit::send()`] bridges APIs that take a callback, as it does not need to be awaited.
+//! ```
+//! # use remit::thread::{self, Remit};
+//! fn visit(f: impl FnMut(&str) -> bool) {
+//!     ["a", "b", "c"].into_iter().all(f);
+//! }
+//! let visited = thread::spawn(|remit: Remit<String>| async move {
+//!     visit(|name| remit.send(name.to_uppercase()));
+//! });
+//! assert_eq!(vec!["A", "B", "C"], visited.collect::<Vec<_>>());
+//! ```
+//!
+//! Work may be split across several generators with [`fan_out()`],
+//! running as many at a time as there are threads available, with their values reassembled in order.
+//! ```
+//! # use remit::thread::{self, Remit};
+//! let lines = thread::fan_out(["a b", "c", "d e f"].map(|file| {
+//!     move |remit: Remit<String>| async move {
+//!         for word in file.split(' ') {
+//!             remit.value(word.repeat(2)).await;
+//!         }
+//!     }
+//! }));
+//! assert_eq!(vec!["aa", "bb", "cc", "dd", "ee", "ff"], lines.collect::<Vec<_>>());
+//! ```
+
+use core::{
+    future::{
+        poll_fn,
+        Future,
+    },
+    pin::pin,
+    task::{
+        Context,
+        Poll,
+    },
+};
+
+use std::{
+    collections::VecDeque,
+    mem,
+    num::NonZeroUsize,
+    panic,
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        mpsc::{
+            self,
+            Receiver,
+            SyncSender,
+        },
+        Arc,
+        Mutex,
+    },
+    task::Wake,
+    thread::{
+        self,
+        JoinHandle,
+        Thread,
+    },
+};
+
+/// The number of values buffered by [`spawn()`], beyond the value being remitted.
+pub const DEFAULT_BOUND: usize = 1;
+
+/// Allows a generator on a dedicated thread to provide values to a [`Spawned`] iterator.
+///
+/// May be cloned, such as to remit from the threads of a callback.
+pub struct Remit<T> {
+    sender: SyncSender<T>,
+    closed: Arc<AtomicBool>,
+}
+
+impl<T> Clone for Remit<T> {
+    fn clone(&self) -> Self {
+        Remit {
+            sender: self.sender.clone(),
+            closed: self.closed.clone(),
+        }
+    }
+}
+
+impl<T> Remit<T> {
+    /// Remits the value to the iterator.
+    ///
+    /// The provided future finishes once the value is in the channel,
+    /// blocking the generator's thread while the channel is full.
+    ///
+    /// If the iterator has been dropped, the value is discarded,
+    /// and the future will always poll as pending, such that the generator is dropped.
+    pub fn value(&self, value: T) -> impl Future<Output=()> + '_ {
+        let mut value = Some(value);
+        poll_fn(move |_| match value.take().map(|value| self.send(value)) {
+            Some(true) => Poll::Ready(()),
+            _ => Poll::Pending,
+        })
+    }
+
+    /// Remits the value to the iterator, blocking while the channel is full.
+    /// Provides `false` if the iterator has been dropped, discarding the value.
+    pub fn send(&self, value: T) -> bool {
+        if self.sender.send(value).is_err() {
+            self.closed.store(true, Ordering::Release);
+            return false
+        }
+        true
+    }
+
+    /// Indicates that the iterator has been dropped, such that values are discarded.
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+}
+
+/// An iterator over the values of generators on dedicated threads.
+///
+/// Dropping the iterator drops the generators once they next remit, without waiting for their threads.
+pub struct Spawned<T> {
+    receiver: Receiver<T>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl<T> Iterator for Spawned<T> {
+    type Item = T;
+
+    /// Blocks until the generator remits a value, or finishes.
+    ///
+    /// If a generator panicked, the panic is resumed once all the values were provided.
+    fn next(&mut self) -> Option<T> {
+        if let Ok(value) = self.receiver.recv() {
+            return Some(value)
+        }
+        for handle in mem::take(&mut self.handles) {
+            if let Err(panic) = handle.join() {
+                panic::resume_unwind(panic)
+            }
+        }
+        None
+    }
+}
+
+/// Runs the generator on a dedicated thread, with a channel of the [`DEFAULT_BOUND`].
+pub fn spawn<T, P>(gen: impl FnOnce(Remit<T>) -> P + Send + 'static) -> Spawned<T>
+    where
+        T: Send + 'static,
+        P: Future<Output=()>,
+{
+    spawn_bounded(DEFAULT_BOUND, gen)
+}
+
+/// Runs the generator on a dedicated thread,
+/// buffering up to the bound of values beyond the value being remitted.
+///
+/// A bound of `0` hands off each value only once the iterator asks for it.
+pub fn spawn_bounded<T, P>(bound: usize, gen: impl FnOnce(Remit<T>) -> P + Send + 'static) -> Spawned<T>
+    where
+        T: Send + 'static,
+        P: Future<Output=()>,
+{
+    let (sender, receiver) = mpsc::sync_channel(bound);
+    Spawned {
+        receiver,
+        handles: vec![worker(sender, gen)],
+    }
+}
+
+/// The number of generators run at a time by [`fan_out()`] and [`fan_out_unordered()`].
+fn available_workers() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// The same as [`fan_out_limited()`], running as many generators at a time as
+/// [`available_parallelism()`](thread::available_parallelism).
+pub fn fan_out<T, P, G>(gens: impl IntoIterator<Item=G>) -> FanOut<T>
+    where
+        T: Send + 'static,
+        P: Future<Output=()>,
+        G: FnOnce(Remit<T>) -> P + Send + 'static,
+{
+    fan_out_limited(available_workers(), gens)
+}
+
+/// Runs each generator on its own thread, providing all the values of each generator in turn.
+///
+/// The generators are run on `workers` threads, each running the next queued generator
+/// once its previous generator finishes.
+/// Each generator buffers up to the [`DEFAULT_BOUND`] while waiting for the generators before it.
+///
+/// ```
+/// # use remit::thread::{self, Remit};
+/// let values = thread::fan_out_limited(2, (0..100).map(|i| {
+///     move |remit: Remit<usize>| async move {
+///         remit.value(i).await;
+///     }
+/// }));
+/// assert_eq!((0..100).collect::<Vec<_>>(), values.collect::<Vec<_>>());
+/// ```
+pub fn fan_out_limited<T, P, G>(workers: usize, gens: impl IntoIterator<Item=G>) -> FanOut<T>
+    where
+        T: Send + 'static,
+        P: Future<Output=()>,
+        G: FnOnce(Remit<T>) -> P + Send + 'static,
+{
+    let mut receivers = VecDeque::new();
+    let queued: Vec<_> = gens.into_iter().map(|gen| {
+        let (sender, receiver) = mpsc::sync_channel(DEFAULT_BOUND);
+        receivers.push_back(receiver);
+        (gen, sender)
+    }).collect();
+    FanOut {
+        receivers,
+        handles: pool(workers, queued),
+    }
+}
+
+/// The same as [`fan_out_unordered_limited()`], running as many generators at a time as
+/// [`available_parallelism()`](thread::available_parallelism).
+///
+/// ```
+/// # use remit::thread::{self, Remit};
+/// let mut squares = thread::fan_out_unordered((0..4).map(|worker| {
+///     move |remit: Remit<u64>| async move {
+///         for i in (worker..16).step_by(4) {
+///             remit.value(i * i).await;
+///         }
+///     }
+/// })).collect::<Vec<_>>();
+/// squares.sort();
+/// assert_eq!((0..16).map(|i| i * i).collect::<Vec<_>>(), squares);
+/// ```
+pub fn fan_out_unordered<T, P, G>(gens: impl IntoIterator<Item=G>) -> Spawned<T>
+    where
+        T: Send + 'static,
+        P: Future<Output=()>,
+        G: FnOnce(Remit<T>) -> P + Send + 'static,
+{
+    fan_out_unordered_limited(available_workers(), gens)
+}
+
+/// Runs the generators on `workers` threads, providing the values as they are remitted.
+///
+/// Each thread runs the next queued generator once its previous generator finishes.
+/// The values of each generator stay in order, but are interleaved with the other generators.
+/// Each thread accounts for the [`DEFAULT_BOUND`] of the shared channel.
+pub fn fan_out_unordered_limited<T, P, G>(workers: usize, gens: impl IntoIterator<Item=G>) -> Spawned<T>
+    where
+        T: Send + 'static,
+        P: Future<Output=()>,
+        G: FnOnce(Remit<T>) -> P + Send + 'static,
+{
+    let gens: Vec<G> = gens.into_iter().collect();
+    let (sender, receiver) = mpsc::sync_channel(DEFAULT_BOUND * workers.clamp(1, gens.len().max(1)));
+    Spawned {
+        receiver,
+        handles: pool(workers, gens.into_iter().map(|gen| (gen, sender.clone())).collect()),
+    }
+}
+
+/// An iterator over the values of generators on dedicated threads,
+/// in the order the generators were provided.
+///
+/// Dropping the iterator drops the generators once they next remit, without waiting for their threads.
+pub struct FanOut<T> {
+    receivers: VecDeque<Receiver<T>>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl<T> Iterator for FanOut<T> {
+    type Item = T;
+
+    /// Blocks until the current generator remits a value, or finishes.
+    ///
+    /// If a generator panicked, the panic is resumed once all the values were provided.
+    fn next(&mut self) -> Option<T> {
+        while let Some(receiver) = self.receivers.front() {
+            if let Ok(value) = receiver.recv() {
+                return Some(value)
+            }
+            self.receivers.pop_front();
+        }
+        for handle in mem::take(&mut self.handles) {
+            if let Err(panic) = handle.join() {
+                panic::resume_unwind(panic)
+            }
+        }
+        None
+    }
+}
+
+/// Runs the generator on a dedicated thread, remitting into the channel.
+fn worker<T, P>(sender: SyncSender<T>, gen: impl FnOnce(Remit<T>) -> P + Send + 'static) -> JoinHandle<()>
+    where
+        T: Send + 'static,
+        P: Future<Output=()>,
+{
+    thread::spawn(move || {
+        let closed = Arc::new(AtomicBool::new(false));
+        let future = gen(Remit {
+            sender,
+            closed: closed.clone(),
+        });
+        block_on(future, &closed);
+    })
+}
+
+/// Runs the queued generators on up to `workers` threads, each remitting into its paired channel.
+///
+/// Each thread takes the next generator once its previous generator finishes,
+/// and resumes the first panic of its generators once the queue is empty.
+fn pool<T, P, G>(workers: usize, queued: Vec<(G, SyncSender<T>)>) -> Vec<JoinHandle<()>>
+    where
+        T: Send + 'static,
+        P: Future<Output=()>,
+        G: FnOnce(Remit<T>) -> P + Send + 'static,
+{
+    let workers = workers.clamp(1, queued.len().max(1));
+    let queue = Arc::new(Mutex::new(queued.into_iter()));
+    (0..workers).map(|_| {
+        let queue = queue.clone();
+        thread::spawn(move || {
+            let mut panicked = None;
+            // The queue is only locked to take the generator, which is never run while locked
+            while let Some((gen, sender)) = queue.lock().ok().and_then(|mut queue| queue.next()) {
+                let closed = Arc::new(AtomicBool::new(false));
+                let run = panic::AssertUnwindSafe(|| block_on(gen(Remit {
+                    sender,
+                    closed: closed.clone(),
+                }), &closed));
+                if let Err(panic) = panic::catch_unwind(run) {
+                    panicked.get_or_insert(panic);
+                }
+            }
+            if let Some(panic) = panicked {
+                panic::resume_unwind(panic)
+            }
+        })
+    }).collect()
+}
+
+/// Wakes the generator's thread.
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls the future until it finishes, or the iterator is dropped, parking while pending.
+fn block_on(future: impl Future<Output=()>, closed: &AtomicBool) {
+    let mut future = pin!(future);
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut context = Context::from_waker(&waker);
+    while future.as_mut().poll(&mut context).is_pending() {
+        if closed.load(Ordering::Acquire) {
+            return
+        }
+        thread::park();
+    }
+}
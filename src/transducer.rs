@@ -0,0 +1,345 @@
+use core::{
+    future::Future,
+    marker::{
+        PhantomData,
+        PhantomPinned,
+    },
+    ops::AsyncFnOnce,
+    pin::Pin,
+    task::{
+        Context,
+        Poll,
+    },
+};
+
+#[cfg(feature = "alloc")]
+use crate::{
+    Cycler,
+    Owner,
+};
+
+use crate::{
+    poll,
+    Exchange,
+    Generator,
+    Mode,
+    Receive,
+    Remit,
+    StaticCall,
+};
+
+/// The future used by a pinned transducer, holding the values not yet received.
+///
+/// The generator is dropped before the values, such that it may still receive or remit while dropping.
+/// ```
+/// # use std::{future::Future, pin::pin, task::{Context, Waker}};
+/// # use std::cell::Cell;
+/// # use remit::{Generator, Receive, Remit};
+/// struct Guard<'a>(&'a Receive<'a, usize>, &'a Remit<'a, usize>, &'a Cell<bool>);
+///
+/// impl Drop for Guard<'_> {
+///     fn drop(&mut self) {
+///         let next = pin!(self.0.next());
+///         let _ = next.poll(&mut Context::from_waker(Waker::noop()));
+///         let _ = self.1.value(0);
+///         self.2.set(true);
+///     }
+/// }
+///
+/// let dropped = Cell::new(false);
+/// {
+///     let mut storage = pin!(Generator::new());
+///     let mut transducer = storage.as_mut().transducer(async |input: Receive<'_, usize>, remit: Remit<'_, usize>| {
+///         let _guard = Guard(&input, &remit, &dropped);
+///         while let Some(value) = input.next().await {
+///             remit.value(value).await;
+///         }
+///     });
+///     transducer.feed(1);
+///     assert_eq!(Some(1), transducer.next());
+///     transducer.feed(2);
+///     transducer.feed(3);
+/// }
+/// assert!(dropped.get());
+/// ```
+pub struct Transduction<I, F> {
+    // NEED: dropped before the input, as the future may receive from it while dropping
+    future: Option<F>,
+    input: Exchange<I>,
+    _pin: PhantomPinned,
+}
+
+impl<I, F: Future<Output=()>> Future for Transduction<I, F> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // SOUND: Pin passthrough; only `future` is inner-pinned.
+        // `future` only ever gets replaced via Option::insert
+        let inner = unsafe { self.get_unchecked_mut() };
+        match &mut inner.future {
+            // SOUND: see above
+            Some(future) => unsafe { Pin::new_unchecked(future) }.poll(cx),
+            None => Poll::Ready(()),
+        }
+    }
+}
+
+impl<T, I, F> Generator<T, Transduction<I, F>> {
+    #[allow(clippy::needless_lifetimes)]
+    /// Takes the pinned storage and the generator and provides a transducer.
+    /// Stack based (does not use an allocation).
+    ///
+    /// The same lifetime relaxation as [`Generator::of()`] applies to the [`Receive`] and [`Remit`].
+    ///
+    /// ```
+    /// # use std::pin::pin;
+    /// # use remit::{Generator, Receive, Remit};
+    /// async fn pairs(input: Receive<'_, usize>, remit: Remit<'_, (usize, usize)>) {
+    ///     while let (Some(first), Some(second)) = (input.next().await, input.next().await) {
+    ///         remit.value((first, second)).await;
+    ///     }
+    /// }
+    /// let mut storage = pin!(Generator::new());
+    /// let mut transducer = storage.as_mut().transducer(pairs);
+    /// transducer.feed(1);
+    /// assert_eq!(None, transducer.next());
+    /// transducer.feed(2);
+    /// assert_eq!(Some((1, 2)), transducer.next());
+    /// transducer.feed(3);
+    /// transducer.finish();
+    /// assert_eq!(None, transducer.next());
+    /// assert!(transducer.is_done());
+    /// // The storage is also finished.
+    /// assert_eq!(None, storage.as_mut().resume_iter().next());
+    /// ```
+    pub fn transducer<'s, G>(
+        self: Pin<&'s mut Self>,
+        gen: G,
+    ) -> Transducer<'s, I, T, Transduction<I, F>>
+        where
+            // insures fn is not implemented only for 'static
+            G: for<'a> AsyncFnOnce(Receive<'a, I>, Remit<'a, T>),
+            // insures F is properly defined, even if it actually has a lifetime
+            G: StaticCall<(Receive<'static, I>, Remit<'static, T>, ), Output=F>,
+    {
+        // SOUND: Pin passthrough; only `future` is inner-pinned.
+        // `future` only ever gets replaced via Option::insert, or dropped in place
+        let inner = unsafe { self.get_unchecked_mut() };
+        inner.clear();
+        let output = Mode::Pinned {
+            exchange: &inner.exchange,
+            // This becomes 'static, and the trait-guard is where the real protection is
+            _lifetime: PhantomData,
+        };
+        let transduction = inner.future.insert(Transduction {
+            future: None,
+            input: Exchange::new(),
+            _pin: PhantomPinned,
+        });
+        let input = Mode::Pinned {
+            exchange: &transduction.input,
+            // This becomes 'static, and the trait-guard is where the real protection is
+            _lifetime: PhantomData,
+        };
+        transduction.future = Some(gen.call_static((Receive(input), Remit(output), )));
+        Transducer {
+            done: false,
+            input,
+            output,
+            future: transduction,
+            #[cfg(feature = "alloc")]
+            _owner: None,
+            #[cfg(feature = "alloc")]
+            _inlet: None,
+        }
+    }
+}
+
+impl<T, P> Generator<T, P> {
+    #[cfg(feature = "alloc")]
+    /// Uses an allocation so that the transducer does not need to be borrowed.
+    ///
+    /// The generator only needs to be valid for `'static`; it does not need to be valid for all lifetimes.
+    ///
+    /// ```
+    /// # use remit::{Generator, Receive, Remit};
+    /// let mut transducer = Generator::boxed_transducer(|input: Receive<'static, &str>, remit| async move {
+    ///     while let Some(line) = input.next().await {
+    ///         for word in line.split_whitespace() {
+    ///             remit.value(word.len()).await;
+    ///         }
+    ///     }
+    /// });
+    /// transducer.feed("Hello World");
+    /// assert_eq!(vec![5, 5], transducer.by_ref().collect::<Vec<_>>());
+    /// transducer.feed("Goodbye");
+    /// transducer.finish();
+    /// assert_eq!(vec![7], transducer.collect::<Vec<_>>());
+    /// ```
+    pub fn boxed_transducer<I>(gen: impl FnOnce(Receive<'static, I>, Remit<'static, T>) -> P) -> Transducer<'static, I, T, P> {
+        let (inlet, input, _) = Cycler::<(), I>::allocate();
+        let (owner, output, future) = Cycler::allocate();
+        // SOUND: Writing to an UnsafeCell.
+        // Only spot where it's being written, having been freshly created.
+        //
+        // NEED: unsafe-cell lets shared-references to not conflict with exclusive-reference to future
+        let future = unsafe { &mut *future }.insert(gen(Receive(input), Remit(output)));
+
+        Transducer {
+            done: false,
+            input,
+            output,
+            future,
+            _owner: Some(owner),
+            _inlet: Some(inlet),
+        }
+    }
+}
+
+/// A generator that both receives and remits values.
+///
+/// Values are pushed in with [`Transducer::feed()`], and the remitted values are provided by iterating.
+/// Like a [`GeneratorIterator`](crate::GeneratorIterator), iterating returns `None` when the generator
+/// awaits without remitting a value, such as when it needs more input.
+/// Calling [`Transducer::finish()`] signals that no more values will be provided,
+/// after which the remaining values can be iterated.
+///
+/// If the generator finishes early, any values subsequently fed are discarded.
+pub struct Transducer<'a, I, T, P> {
+    done: bool,
+    input: Mode<'a, I>,
+    output: Mode<'a, T>,
+    future: *mut P,
+    #[cfg(feature = "alloc")]
+    _owner: Option<Owner<T>>,
+    #[cfg(feature = "alloc")]
+    _inlet: Option<Owner<I>>,
+}
+
+impl<I, T, P> Transducer<'_, I, T, P> {
+    /// Provides a value to the generator, to be received when next iterated.
+    ///
+    /// If a value is fed before the generator receives the previous value,
+    /// the value is buffered with `alloc`.
+    /// Otherwise, like [`Remit::value()`], the behavior is SemVer
+    /// [unspecified](https://doc.rust-lang.org/reference/behavior-not-considered-unsafe.html).
+    pub fn feed(&mut self, value: I) {
+        if !self.done {
+            self.input.push(value);
+        }
+    }
+
+    /// Signals the end of input to the generator.
+    pub fn finish(&mut self) {
+        self.input.close();
+    }
+
+    /// Indicates that the generator has finished, and will not receive any more values.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Indicates that all values fed have been received by the generator.
+    pub fn is_hungry(&self) -> bool {
+        !self.done && !self.input.is_closed() && self.input.is_empty()
+    }
+}
+
+impl<I, T, P: Future<Output=()>> Iterator for Transducer<'_, I, T, P> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if let Some(value) = self.output.next() {
+            return Some(value)
+        }
+        if self.done {
+            return None
+        }
+        // SOUND: see GeneratorIterator::next()
+        if let Poll::Ready(()) = unsafe { poll(self.future) } {
+            self.done = true;
+            self.output.finish();
+        }
+        self.output.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.output.len();
+        if self.done {
+            (len, Some(len))
+        } else {
+            (len, None)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Provides [`Transduce::transduce()`] for all iterators.
+pub trait Transduce: Iterator + Sized {
+    /// Feeds this iterator through a boxed transducer, lazily providing the remitted values.
+    ///
+    /// The generator only needs to be valid for `'static`; it does not need to be valid for all lifetimes.
+    ///
+    /// ```
+    /// # use remit::{Receive, Remit, Transduce};
+    /// async fn runs(input: Receive<'static, char>, remit: Remit<'static, (char, usize)>) {
+    ///     let Some(mut current) = input.next().await else { return };
+    ///     let mut count = 1;
+    ///     while let Some(c) = input.next().await {
+    ///         if c == current {
+    ///             count += 1;
+    ///         } else {
+    ///             remit.value((current, count)).await;
+    ///             current = c;
+    ///             count = 1;
+    ///         }
+    ///     }
+    ///     remit.value((current, count)).await;
+    /// }
+    /// assert_eq!(
+    ///     vec![('a', 3), ('b', 1), ('c', 2)],
+    ///     "aaabcc".chars().transduce(runs).collect::<Vec<_>>(),
+    /// );
+    /// ```
+    fn transduce<T, P>(self, gen: impl FnOnce(Receive<'static, Self::Item>, Remit<'static, T>) -> P) -> Transduced<Self, T, P> {
+        Transduced {
+            upstream: Some(self),
+            transducer: Generator::boxed_transducer(gen),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<U: Iterator> Transduce for U {}
+
+#[cfg(feature = "alloc")]
+/// An iterator over the values remitted by a transducer, as fed by an upstream iterator.
+///
+/// The upstream iterator is only advanced when the transducer needs more input.
+pub struct Transduced<U: Iterator, T, P> {
+    upstream: Option<U>,
+    transducer: Transducer<'static, U::Item, T, P>,
+}
+
+#[cfg(feature = "alloc")]
+impl<U: Iterator, T, P: Future<Output=()>> Iterator for Transduced<U, T, P> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            if let Some(value) = self.transducer.next() {
+                return Some(value)
+            }
+            if !self.transducer.is_hungry() {
+                return None
+            }
+            match self.upstream.as_mut().and_then(Iterator::next) {
+                Some(value) => self.transducer.feed(value),
+                None => {
+                    self.upstream = None;
+                    self.transducer.finish();
+                },
+            }
+        }
+    }
+}